@@ -0,0 +1,194 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crate::{Dictionary, MatchTarget};
+
+/// How completions are ordered.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum CompletionRanking {
+    /// Shorter words first, and then in alphabetical order.
+    #[default]
+    Shortest,
+    /// In alphabetical order, ignoring case.
+    Alphabetical,
+    /// In the order of the entries.
+    Index,
+}
+
+/// A word which starts with a prefix.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Completion {
+    /// The index of the entry.
+    pub index:  usize,
+    pub word:   String,
+    /// For right words, the position of the current right word in the history is included.
+    pub target: MatchTarget,
+}
+
+/// A node of a trie over case-folded words.
+#[derive(Debug, Clone, Default)]
+struct TrieNode {
+    children: BTreeMap<char, TrieNode>,
+    /// The words ending at this node, with their left words, which identify their entries.
+    words:    Vec<(String, String)>,
+}
+
+impl TrieNode {
+    fn insert(&mut self, word: &str, left: &str) {
+        let mut node = self;
+
+        for c in word.chars().flat_map(char::to_lowercase) {
+            node = node.children.entry(c).or_default();
+        }
+
+        node.words.push((String::from(word), String::from(left)));
+    }
+
+    /// Remove a word. Return whether this node becomes empty.
+    fn remove(&mut self, mut chars: impl Iterator<Item = char>, word: &str, left: &str) -> bool {
+        match chars.next() {
+            Some(c) => {
+                if let Some(child) = self.children.get_mut(&c) {
+                    if child.remove(chars, word, left) {
+                        self.children.remove(&c);
+                    }
+                }
+            },
+            None => {
+                if let Some(p) = self.words.iter().position(|(w, l)| w == word && l == left) {
+                    self.words.remove(p);
+                }
+            },
+        }
+
+        self.children.is_empty() && self.words.is_empty()
+    }
+
+    fn find(&self, prefix: &str) -> Option<&TrieNode> {
+        let mut node = self;
+
+        for c in prefix.chars().flat_map(char::to_lowercase) {
+            node = node.children.get(&c)?;
+        }
+
+        Some(node)
+    }
+
+    fn collect<'a>(&'a self, words: &mut Vec<&'a (String, String)>) {
+        words.extend(self.words.iter());
+
+        for child in self.children.values() {
+            child.collect(words);
+        }
+    }
+}
+
+/// A prefix index over case-folded left words and current right words.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PrefixIndex {
+    left:    TrieNode,
+    right:   TrieNode,
+    /// The indices of the entries by their left words.
+    indices: HashMap<String, usize>,
+    ranking: CompletionRanking,
+}
+
+impl PrefixIndex {
+    #[inline]
+    pub(crate) fn insert(&mut self, left: &str, right: &str) {
+        self.left.insert(left, left);
+        self.right.insert(right, left);
+    }
+
+    #[inline]
+    pub(crate) fn remove(&mut self, left: &str, right: &str) {
+        self.left.remove(left.chars().flat_map(char::to_lowercase), left, left);
+        self.right.remove(right.chars().flat_map(char::to_lowercase), right, left);
+    }
+}
+
+impl Dictionary {
+    /// Set how completions are ordered.
+    #[inline]
+    pub fn set_completion_ranking(&mut self, ranking: CompletionRanking) {
+        self.prefix_index.ranking = ranking;
+    }
+
+    pub(crate) fn rebuild_prefix_index(&mut self) {
+        let mut prefix_index = PrefixIndex {
+            ranking: self.prefix_index.ranking,
+            ..PrefixIndex::default()
+        };
+
+        for (left, right) in self.left.iter().zip(self.right.iter()) {
+            prefix_index.insert(left, right.last().unwrap());
+        }
+
+        self.prefix_index = prefix_index;
+
+        self.refresh_entry_indices();
+    }
+
+    /// Map the left words to the indices of their entries, which change after the entries are sorted.
+    pub(crate) fn refresh_entry_indices(&mut self) {
+        self.prefix_index.indices =
+            self.left.iter().enumerate().map(|(index, left)| (left.clone(), index)).collect();
+    }
+
+    fn complete(&self, trie: &TrieNode, prefix: &str, limit: usize, left: bool) -> Vec<Completion> {
+        let mut words = Vec::new();
+
+        if let Some(node) = trie.find(prefix) {
+            node.collect(&mut words);
+        }
+
+        let indices = &self.prefix_index.indices;
+
+        let mut words: Vec<(usize, &String)> = words
+            .into_iter()
+            .filter_map(|(word, left_word)| Some((*indices.get(left_word)?, word)))
+            .collect();
+
+        match self.prefix_index.ranking {
+            CompletionRanking::Index => words.sort_by_key(|(index, _)| *index),
+            ranking => words.sort_by_cached_key(|(_, word)| {
+                let length =
+                    if ranking == CompletionRanking::Shortest { word.chars().count() } else { 0 };
+
+                (length, word.to_uppercase())
+            }),
+        }
+
+        words.truncate(limit);
+
+        words
+            .into_iter()
+            .map(|(index, word)| {
+                let target = if left {
+                    MatchTarget::Left
+                } else {
+                    MatchTarget::Right {
+                        history_index: self.right[index].len() - 1
+                    }
+                };
+
+                Completion {
+                    index,
+                    word: word.clone(),
+                    target,
+                }
+            })
+            .collect()
+    }
+
+    /// Get at most `limit` left words starting with a prefix, ignoring case.
+    #[inline]
+    pub fn complete_left<S: AsRef<str>>(&self, prefix: S, limit: usize) -> Vec<Completion> {
+        self.complete(&self.prefix_index.left, prefix.as_ref(), limit, true)
+    }
+
+    /// Get at most `limit` current right words starting with a prefix, ignoring case.
+    #[inline]
+    pub fn complete_right<S: AsRef<str>>(&self, prefix: S, limit: usize) -> Vec<Completion> {
+        self.complete(&self.prefix_index.right, prefix.as_ref(), limit, false)
+    }
+}
@@ -0,0 +1,177 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+};
+
+use crate::Dictionary;
+
+/// A difference of an entry between two dictionaries. Left words are compared ignoring the case of ASCII letters.
+#[derive(Debug, Clone, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "kind", rename_all = "snake_case"))]
+pub enum EntryChange {
+    /// The entry only exists in the new dictionary.
+    Added { left: String, right: Vec<String> },
+    /// The entry only exists in the old dictionary.
+    Removed { left: String, right: Vec<String> },
+    /// The current right word is changed, which means the term is retranslated.
+    Retranslated { left: String, old_right: Vec<String>, new_right: Vec<String> },
+    /// The current right word is kept, but older right words are added or removed, for example by merging.
+    HistoryChanged { left: String, old_right: Vec<String>, new_right: Vec<String> },
+}
+
+impl EntryChange {
+    /// Get the left word of the changed entry. It is the new one if there is.
+    #[inline]
+    pub fn get_left(&self) -> &str {
+        match self {
+            EntryChange::Added {
+                left, ..
+            }
+            | EntryChange::Removed {
+                left, ..
+            }
+            | EntryChange::Retranslated {
+                left, ..
+            }
+            | EntryChange::HistoryChanged {
+                left, ..
+            } => left,
+        }
+    }
+}
+
+impl Display for EntryChange {
+    /// Write the change as a line like `retranslated Dwemer = 德魏默 => 德威默`.
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            EntryChange::Added {
+                left,
+                right,
+            } => f.write_fmt(format_args!("added {} = {}", left, right.join(" --> "))),
+            EntryChange::Removed {
+                left,
+                right,
+            } => f.write_fmt(format_args!("removed {} = {}", left, right.join(" --> "))),
+            EntryChange::Retranslated {
+                left,
+                old_right,
+                new_right,
+            } => f.write_fmt(format_args!(
+                "retranslated {} = {} => {}",
+                left,
+                old_right.last().unwrap(),
+                new_right.last().unwrap()
+            )),
+            EntryChange::HistoryChanged {
+                left,
+                old_right,
+                new_right,
+            } => f.write_fmt(format_args!(
+                "history changed {} = {} => {}",
+                left,
+                old_right.join(" --> "),
+                new_right.join(" --> ")
+            )),
+        }
+    }
+}
+
+/// The differences between two dictionaries, ordered like a dictionary file.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DictionaryDiff {
+    pub changes: Vec<EntryChange>,
+}
+
+impl DictionaryDiff {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl Display for DictionaryDiff {
+    /// Write one change per line.
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        for (i, change) in self.changes.iter().enumerate() {
+            if i > 0 {
+                f.write_str("\n")?;
+            }
+
+            Display::fmt(change, f)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Dictionary {
+    /// Compare this dictionary, as the old one, with a new one.
+    pub fn diff(&self, new: &Dictionary) -> DictionaryDiff {
+        let new_indices: HashMap<String, usize> = new
+            .left
+            .iter()
+            .enumerate()
+            .map(|(index, left)| (left.to_ascii_lowercase(), index))
+            .collect();
+
+        let mut matched = vec![false; new.count()];
+        let mut changes = Vec::new();
+
+        for (left, old_right) in self.iter() {
+            let index = match new_indices.get(&left.to_ascii_lowercase()) {
+                Some(&index) => index,
+                None => {
+                    changes.push(EntryChange::Removed {
+                        left:  String::from(left),
+                        right: old_right.to_vec(),
+                    });
+
+                    continue;
+                },
+            };
+
+            matched[index] = true;
+
+            let new_right = &new.right[index];
+
+            if old_right == new_right.as_slice() {
+                continue;
+            }
+
+            let left = new.left[index].clone();
+            let old_right = old_right.to_vec();
+            let new_right = new_right.clone();
+
+            changes.push(if old_right.last() == new_right.last() {
+                EntryChange::HistoryChanged {
+                    left,
+                    old_right,
+                    new_right,
+                }
+            } else {
+                EntryChange::Retranslated {
+                    left,
+                    old_right,
+                    new_right,
+                }
+            });
+        }
+
+        changes.extend(new.iter().zip(matched).filter(|(_, matched)| !matched).map(
+            |((left, right), _)| EntryChange::Added {
+                left:  String::from(left),
+                right: right.to_vec(),
+            },
+        ));
+
+        changes.sort_by_cached_key(|change| change.get_left().to_uppercase());
+
+        DictionaryDiff {
+            changes,
+        }
+    }
+}
@@ -0,0 +1,213 @@
+use crate::{
+    combine_histories, double_metaphone, edit_distance, Dictionary, MergeEntriesError,
+    OperationKind, WriteError,
+};
+
+/// Why two left words look like duplicates. The variants are ordered from the strongest to the weakest.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum DuplicateReason {
+    /// The words are equal after ignoring case, whitespace and punctuation.
+    Normalized,
+    /// The normalized words are within an edit distance.
+    EditDistance(usize),
+    /// The words share a Double Metaphone key.
+    Phonetic,
+}
+
+/// Two entries which look like duplicates.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct DuplicateLink {
+    /// The index of the former entry.
+    pub a:      usize,
+    /// The index of the latter entry.
+    pub b:      usize,
+    pub reason: DuplicateReason,
+}
+
+/// A group of entries which are linked as duplicates, directly or through other entries.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DuplicateCluster {
+    /// The indices of the entries, in order.
+    pub indices: Vec<usize>,
+    pub links:   Vec<DuplicateLink>,
+}
+
+/// Options of `Dictionary::find_duplicates`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct DuplicateOptions {
+    /// The maximum edit distance between normalized left words. It is also limited to a third of the shorter word, so short words need to be equal. `0` disables it.
+    pub max_distance: usize,
+    /// Whether to link words which share a Double Metaphone key of at least three sounds.
+    pub phonetic:     bool,
+}
+
+impl Default for DuplicateOptions {
+    #[inline]
+    fn default() -> Self {
+        DuplicateOptions {
+            max_distance: 1, phonetic: true
+        }
+    }
+}
+
+/// Lowercase a word and drop whitespace and punctuation.
+#[inline]
+fn normalize(s: &str) -> String {
+    s.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect()
+}
+
+/// Find the root of a set, halving the path.
+fn find_root(parents: &mut [usize], mut i: usize) -> usize {
+    while parents[i] != i {
+        parents[i] = parents[parents[i]];
+
+        i = parents[i];
+    }
+
+    i
+}
+
+impl Dictionary {
+    /// Cluster entries whose left words are near duplicates, like `Dwemer` and `Dwemmer`, or `Dwarven Ruin` and `Dwarven-Ruin`. Entries which have no duplicates are not included.
+    pub fn find_duplicates(&self, options: &DuplicateOptions) -> Vec<DuplicateCluster> {
+        let size = self.count();
+
+        let normalized: Vec<String> = self.left.iter().map(|s| normalize(s)).collect();
+
+        let keys: Vec<_> = self
+            .left
+            .iter()
+            .map(|s| {
+                let key = double_metaphone(s);
+
+                if options.phonetic && key.primary.len() >= 3 {
+                    Some(key)
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        let mut links = Vec::new();
+
+        for a in 0..size {
+            for b in (a + 1)..size {
+                let reason = if !normalized[a].is_empty() && normalized[a] == normalized[b] {
+                    Some(DuplicateReason::Normalized)
+                } else {
+                    let max_distance = options
+                        .max_distance
+                        .min(normalized[a].chars().count() / 3)
+                        .min(normalized[b].chars().count() / 3);
+
+                    let distance = if max_distance > 0 {
+                        edit_distance(&normalized[a], &normalized[b])
+                    } else {
+                        usize::MAX
+                    };
+
+                    if distance <= max_distance {
+                        Some(DuplicateReason::EditDistance(distance))
+                    } else {
+                        match (&keys[a], &keys[b]) {
+                            (Some(key_a), Some(key_b)) if key_a.sounds_like(key_b) => {
+                                Some(DuplicateReason::Phonetic)
+                            },
+                            _ => None,
+                        }
+                    }
+                };
+
+                if let Some(reason) = reason {
+                    links.push(DuplicateLink {
+                        a,
+                        b,
+                        reason,
+                    });
+                }
+            }
+        }
+
+        let mut parents: Vec<usize> = (0..size).collect();
+
+        for link in links.iter() {
+            let root_a = find_root(&mut parents, link.a);
+            let root_b = find_root(&mut parents, link.b);
+
+            parents[root_b.max(root_a)] = root_b.min(root_a);
+        }
+
+        let mut clusters: Vec<DuplicateCluster> = Vec::new();
+        let mut cluster_indices = vec![usize::MAX; size];
+
+        for link in links {
+            let root = find_root(&mut parents, link.a);
+
+            if cluster_indices[root] == usize::MAX {
+                cluster_indices[root] = clusters.len();
+
+                clusters.push(DuplicateCluster {
+                    indices: Vec::new(), links: Vec::new()
+                });
+            }
+
+            clusters[cluster_indices[root]].links.push(link);
+        }
+
+        for index in 0..size {
+            let root = find_root(&mut parents, index);
+
+            if cluster_indices[root] != usize::MAX {
+                clusters[cluster_indices[root]].indices.push(index);
+            }
+        }
+
+        clusters.sort_by_key(|cluster| cluster.indices[0]);
+
+        clusters
+    }
+
+    /// Merge the entry at `b` into the entry at `a`. The left word of `a` is kept, and the right words of `b` which are not in the history of `a` are inserted before the current right word, in order. Return `Ok(false)` if either index is out of range or they are equal.
+    ///
+    /// The current right words of the two entries need to be equal. Otherwise, a `MergeEntriesError::Conflict` is returned, and the entries can be made to agree by `add_edit` first.
+    pub fn merge_entries(&mut self, a: usize, b: usize) -> Result<bool, MergeEntriesError> {
+        let size = self.count();
+
+        if a >= size || b >= size || a == b {
+            return Ok(false);
+        }
+
+        let right_string = self.right[a].last().unwrap();
+        let other_right_string = self.right[b].last().unwrap();
+
+        if right_string != other_right_string {
+            return Err(MergeEntriesError::Conflict {
+                left_string:        self.left[b].clone(),
+                right_string:       right_string.clone(),
+                other_right_string: other_right_string.clone(),
+            });
+        }
+
+        if self.right[b].contains(&self.left[a]) {
+            return Err(WriteError::Same.into());
+        }
+
+        let snapshot = self.snapshot();
+
+        let left = self.left.remove(b);
+        let right = self.right.remove(b);
+
+        // the entry at `a` moves forward if it is after `b`
+        let a = if a > b { a - 1 } else { a };
+
+        self.right[a] = combine_histories(&self.right[a], &right);
+
+        self.prefix_index.remove(&left, self.right[a].last().unwrap());
+
+        self.record(OperationKind::MergeEntries, snapshot);
+
+        self.write_data()?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,47 @@
+use crate::Dictionary;
+
+/// A left word with the history of its right words.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Entry {
+    /// The left word.
+    pub left:  String,
+    /// The right words, from the oldest one to the current one.
+    pub right: Vec<String>,
+}
+
+impl Entry {
+    /// Create a new `Entry` instance.
+    #[inline]
+    pub fn new<L: Into<String>>(left: L, right: Vec<String>) -> Entry {
+        Entry {
+            left: left.into(),
+            right,
+        }
+    }
+
+    /// Get the current (last) right word.
+    #[inline]
+    pub fn get_right(&self) -> Option<&str> {
+        self.right.last().map(|s| s.as_str())
+    }
+}
+
+impl Dictionary {
+    /// Get the left word and all right words at a specific index as an `Entry`.
+    #[inline]
+    pub fn get_entry(&self, index: usize) -> Option<Entry> {
+        match (self.left.get(index), self.right.get(index)) {
+            (Some(left), Some(right)) => Some(Entry::new(left.as_str(), right.clone())),
+            _ => None,
+        }
+    }
+}
+
+impl Dictionary {
+    /// Iterate over the left words and all right words of the entries in order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.left.iter().map(|s| s.as_str()).zip(self.right.iter().map(|v| v.as_slice()))
+    }
+}
@@ -107,3 +107,114 @@ impl Display for WriteError {
 }
 
 impl Error for WriteError {}
+
+#[derive(Debug)]
+pub enum FormatError {
+    IOError(io::Error),
+    Malformed { line: usize, reason: String },
+}
+
+impl From<io::Error> for FormatError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        FormatError::IOError(error)
+    }
+}
+
+impl Display for FormatError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            FormatError::IOError(err) => Display::fmt(&err, f),
+            FormatError::Malformed {
+                line,
+                reason,
+            } => f.write_fmt(format_args!("malformed at line {}, {}", line, reason)),
+        }
+    }
+}
+
+impl Error for FormatError {}
+
+#[derive(Debug)]
+pub enum ImportError {
+    FormatError(FormatError),
+    WriteError(WriteError),
+}
+
+impl From<FormatError> for ImportError {
+    #[inline]
+    fn from(error: FormatError) -> Self {
+        ImportError::FormatError(error)
+    }
+}
+
+impl From<WriteError> for ImportError {
+    #[inline]
+    fn from(error: WriteError) -> Self {
+        ImportError::WriteError(error)
+    }
+}
+
+impl From<io::Error> for ImportError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        ImportError::FormatError(FormatError::IOError(error))
+    }
+}
+
+impl Display for ImportError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            ImportError::FormatError(err) => Display::fmt(&err, f),
+            ImportError::WriteError(err) => Display::fmt(&err, f),
+        }
+    }
+}
+
+impl Error for ImportError {}
+
+#[derive(Debug)]
+pub enum MergeEntriesError {
+    WriteError(WriteError),
+    /// Two entries cannot be merged because their current right words are different.
+    Conflict {
+        left_string:        String,
+        right_string:       String,
+        other_right_string: String,
+    },
+}
+
+impl From<WriteError> for MergeEntriesError {
+    #[inline]
+    fn from(error: WriteError) -> Self {
+        MergeEntriesError::WriteError(error)
+    }
+}
+
+impl From<io::Error> for MergeEntriesError {
+    #[inline]
+    fn from(error: io::Error) -> Self {
+        MergeEntriesError::WriteError(WriteError::IOError(error))
+    }
+}
+
+impl Display for MergeEntriesError {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match self {
+            MergeEntriesError::WriteError(err) => Display::fmt(&err, f),
+            MergeEntriesError::Conflict {
+                left_string,
+                right_string,
+                other_right_string,
+            } => f.write_fmt(format_args!(
+                "the left word {:?} is mapped to {:?} rather than {:?}",
+                left_string, other_right_string, right_string
+            )),
+        }
+    }
+}
+
+impl Error for MergeEntriesError {}
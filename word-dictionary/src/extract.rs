@@ -0,0 +1,306 @@
+use std::{collections::HashMap, ops::Range};
+
+use crate::Dictionary;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TermCandidateKind {
+    /// A run of capitalized words, like `Throat of the World`.
+    Name,
+    /// A recurring sequence of words.
+    NGram,
+}
+
+/// A term which is found in source texts but is not a left word yet.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TermCandidate {
+    /// The term as it is first written in the texts.
+    pub term:      String,
+    pub kind:      TermCandidateKind,
+    pub frequency: usize,
+    /// Snippets of text around the first occurrences.
+    pub contexts:  Vec<String>,
+}
+
+/// Options of `Dictionary::extract_terms`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ExtractionOptions {
+    /// Terms occurring fewer times are dropped.
+    pub min_frequency: usize,
+    /// The maximum count of words in an n-gram. Names are not limited.
+    pub max_words:     usize,
+    /// The maximum count of contexts collected for each candidate.
+    pub max_contexts:  usize,
+    /// The count of characters kept on each side of an occurrence in a context.
+    pub context_width: usize,
+}
+
+impl Default for ExtractionOptions {
+    #[inline]
+    fn default() -> Self {
+        ExtractionOptions {
+            min_frequency: 2,
+            max_words:     3,
+            max_contexts:  3,
+            context_width: 30,
+        }
+    }
+}
+
+/// Common English words, which cannot start or end an n-gram.
+const STOP_WORDS: [&str; 48] = [
+    "a", "an", "and", "are", "as", "at", "be", "but", "by", "can", "do", "for", "from", "had",
+    "has", "have", "he", "her", "his", "i", "if", "in", "is", "it", "its", "me", "my", "no", "not",
+    "of", "on", "or", "our", "she", "so", "that", "the", "their", "they", "this", "to", "was",
+    "we", "were", "will", "with", "you", "your",
+];
+
+/// Lowercase words which can join the capitalized words of a name.
+const NAME_CONNECTORS: [&str; 6] = ["of", "the", "and", "de", "von", "van"];
+
+struct Token {
+    range:       Range<usize>,
+    capitalized: bool,
+}
+
+/// Split a text into words. Apostrophes and hyphens inside words are kept.
+fn tokenize(s: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = Vec::new();
+
+    let mut start: Option<usize> = None;
+
+    let mut chars = s.char_indices().peekable();
+
+    while let Some((i, c)) = chars.next() {
+        let inner_punctuation = start.is_some()
+            && matches!(c, '\'' | '\u{2019}' | '-')
+            && chars.peek().map_or(false, |(_, next)| next.is_alphanumeric());
+
+        if c.is_alphanumeric() || inner_punctuation {
+            if start.is_none() {
+                start = Some(i);
+            }
+        } else if let Some(token_start) = start.take() {
+            tokens.push(Token {
+                range:       token_start..i,
+                capitalized: s[token_start..].starts_with(char::is_uppercase),
+            });
+        }
+    }
+
+    if let Some(token_start) = start {
+        tokens.push(Token {
+            range:       token_start..s.len(),
+            capitalized: s[token_start..].starts_with(char::is_uppercase),
+        });
+    }
+
+    tokens
+}
+
+/// Whether two tokens are only separated by whitespace.
+#[inline]
+fn is_joined(s: &str, a: &Token, b: &Token) -> bool {
+    s[a.range.end..b.range.start].chars().all(char::is_whitespace)
+}
+
+/// Whether a token is at the start of a sentence.
+#[inline]
+fn is_sentence_start(s: &str, token: &Token) -> bool {
+    let before = s[..token.range.start].trim_end();
+
+    before.is_empty() || before.ends_with(['.', '!', '?', ':', '\n'])
+}
+
+#[inline]
+fn is_stop_word(word: &str) -> bool {
+    STOP_WORDS.iter().any(|w| w.eq_ignore_ascii_case(word))
+}
+
+struct Counter {
+    term:      String,
+    kind:      TermCandidateKind,
+    words:     usize,
+    frequency: usize,
+    contexts:  Vec<String>,
+}
+
+/// Get a snippet around a range, without broken words at its ends and with whitespace collapsed.
+fn context(s: &str, range: Range<usize>, width: usize) -> String {
+    let mut start =
+        s[..range.start].char_indices().rev().nth(width.saturating_sub(1)).map_or(0, |(i, _)| i);
+    let mut end = s[range.end..].char_indices().nth(width).map_or(s.len(), |(i, _)| range.end + i);
+
+    if s[..start].ends_with(char::is_alphanumeric) {
+        start = s[start..range.start].find(char::is_whitespace).map_or(range.start, |p| start + p);
+    }
+
+    if s[end..].starts_with(char::is_alphanumeric) {
+        end = s[range.end..end].rfind(char::is_whitespace).map_or(range.end, |p| range.end + p);
+    }
+
+    s[start..end].split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+impl Dictionary {
+    /// Find recurring capitalized names and frequent n-grams in source texts which are not left words yet. Candidates are ranked by their frequencies weighted by their lengths in words. N-grams cannot start or end with common English words, and a candidate is dropped if it only occurs inside a longer candidate.
+    pub fn extract_terms<I: IntoIterator<Item = S>, S: AsRef<str>>(
+        &self,
+        texts: I,
+        options: &ExtractionOptions,
+    ) -> Vec<TermCandidate> {
+        let mut counters: HashMap<String, Counter> = HashMap::new();
+
+        let mut count = |s: &str, tokens: &[Token], kind: TermCandidateKind| {
+            let range = tokens[0].range.start..tokens[tokens.len() - 1].range.end;
+
+            let term = tokens.iter().map(|t| &s[t.range.clone()]).collect::<Vec<&str>>().join(" ");
+
+            let counter = counters.entry(term.to_lowercase()).or_insert_with(|| Counter {
+                term,
+                kind,
+                words: tokens.len(),
+                frequency: 0,
+                contexts: Vec::new(),
+            });
+
+            counter.frequency += 1;
+
+            if counter.kind == TermCandidateKind::NGram && kind == TermCandidateKind::Name {
+                counter.kind = kind;
+            }
+
+            if counter.contexts.len() < options.max_contexts {
+                let context = context(s, range, options.context_width);
+
+                if !counter.contexts.contains(&context) {
+                    counter.contexts.push(context);
+                }
+            }
+        };
+
+        for s in texts {
+            let s = s.as_ref();
+
+            let tokens = tokenize(s);
+
+            // names
+            let mut i = 0;
+
+            while i < tokens.len() {
+                if !tokens[i].capitalized {
+                    i += 1;
+                    continue;
+                }
+
+                let mut end = i + 1;
+
+                while end < tokens.len() && is_joined(s, &tokens[end - 1], &tokens[end]) {
+                    if tokens[end].capitalized {
+                        end += 1;
+                        continue;
+                    }
+
+                    // connectors like `of` in `Throat of the World`
+                    let mut j = end;
+
+                    while j < tokens.len()
+                        && !tokens[j].capitalized
+                        && is_joined(s, &tokens[j - 1], &tokens[j])
+                        && NAME_CONNECTORS.contains(&&s[tokens[j].range.clone()])
+                    {
+                        j += 1;
+                    }
+
+                    if j > end
+                        && j < tokens.len()
+                        && tokens[j].capitalized
+                        && is_joined(s, &tokens[j - 1], &tokens[j])
+                    {
+                        end = j + 1;
+                    } else {
+                        break;
+                    }
+                }
+
+                // a capitalized word starting a sentence is not a part of the name when a connector follows it, like `Climb` in `Climb the Throat of the World`
+                if end - i >= 3 && is_sentence_start(s, &tokens[i]) && !tokens[i + 1].capitalized {
+                    i += 1;
+                    while !tokens[i].capitalized {
+                        i += 1;
+                    }
+                }
+
+                if end - i >= 2 {
+                    count(s, &tokens[i..end], TermCandidateKind::Name);
+                }
+
+                i = end;
+            }
+
+            // n-grams
+            for n in 2..=options.max_words {
+                for window in tokens.windows(n) {
+                    let first = &s[window[0].range.clone()];
+                    let last = &s[window[n - 1].range.clone()];
+
+                    if is_stop_word(first)
+                        || is_stop_word(last)
+                        || window.iter().all(|t| t.capitalized)
+                        || window.iter().any(|t| s[t.range.clone()].chars().all(|c| c.is_numeric()))
+                        || !window.windows(2).all(|w| is_joined(s, &w[0], &w[1]))
+                    {
+                        continue;
+                    }
+
+                    count(s, window, TermCandidateKind::NGram);
+                }
+            }
+        }
+
+        let counters: Vec<(String, Counter)> = counters
+            .into_iter()
+            .filter(|(_, c)| {
+                c.frequency >= options.min_frequency
+                    && !self.left.iter().any(|left| left.eq_ignore_ascii_case(&c.term))
+            })
+            .collect();
+
+        // the frequencies of the candidates containing each word sub-sequence
+        let mut containing: HashMap<String, Vec<usize>> = HashMap::new();
+
+        for (key, c) in counters.iter() {
+            let words: Vec<&str> = key.split(' ').collect();
+
+            for n in 1..words.len() {
+                for window in words.windows(n) {
+                    containing.entry(window.join(" ")).or_default().push(c.frequency);
+                }
+            }
+        }
+
+        let mut candidates: Vec<(usize, TermCandidate)> = counters
+            .into_iter()
+            // drop nested candidates which never occur on their own
+            .filter(|(key, c)| {
+                containing.get(key).map_or(true, |frequencies| !frequencies.contains(&c.frequency))
+            })
+            .map(|(_, c)| {
+                (c.frequency * c.words, TermCandidate {
+                    term:      c.term,
+                    kind:      c.kind,
+                    frequency: c.frequency,
+                    contexts:  c.contexts,
+                })
+            })
+            .collect();
+
+        candidates.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then(b.frequency.cmp(&a.frequency))
+                .then_with(|| a.term.cmp(&b.term))
+        });
+
+        candidates.into_iter().map(|(_, c)| c).collect()
+    }
+}
@@ -0,0 +1,154 @@
+use crate::{
+    xml::{XmlDocument, XmlElement, XmlNode},
+    FormatError,
+};
+
+/// Decode the escape sequences of a resource string, like `\'` and `\u2019`. Unescaped double quotes, which keep whitespace, are dropped, and whether there were any is returned. Return `None` if an escape sequence is not supported.
+fn unescape(s: &str) -> Option<(String, bool)> {
+    let mut output = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    let mut quoted = false;
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => (),
+            '"' => {
+                quoted = true;
+                continue;
+            },
+            _ => {
+                output.push(c);
+                continue;
+            },
+        }
+
+        let c = match chars.next()? {
+            'n' => '\n',
+            't' => '\t',
+            'u' => {
+                let digits: String = chars.by_ref().take(4).collect();
+
+                if digits.len() != 4 {
+                    return None;
+                }
+
+                u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32)?
+            },
+            c @ ('\'' | '"' | '\\' | '@' | '?') => c,
+            _ => return None,
+        };
+
+        output.push(c);
+    }
+
+    Some((output, quoted))
+}
+
+fn escape(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    // a leading `@` or `?` would make the string a reference
+    if s.starts_with(['@', '?']) {
+        output.push('\\');
+    }
+
+    for c in s.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '\'' => output.push_str("\\'"),
+            '"' => output.push_str("\\\""),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Walk the text nodes in a resource value. The content of `<xliff:g>` elements is not translatable, so it is skipped.
+fn walk_value(
+    element: &mut XmlElement,
+    key: &str,
+    line: usize,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+) {
+    for node in element.children.iter_mut() {
+        match node {
+            XmlNode::Element(e) => {
+                if !e.is("g") {
+                    walk_value(e, key, line, f);
+                }
+            },
+            XmlNode::Text {
+                text: t, ..
+            }
+            | XmlNode::CData(t) => {
+                // references like `@string/app_name` and `?attr/title`
+                if t.trim().is_empty() || t.starts_with(['@', '?']) {
+                    continue;
+                }
+
+                if let Some((unescaped, quoted)) = unescape(t) {
+                    if let Some(new_t) = f(key, line, &unescaped) {
+                        *t =
+                            if quoted { format!("\"{}\"", escape(&new_t)) } else { escape(&new_t) };
+                    }
+                }
+            },
+            XmlNode::Other(_) | XmlNode::Reference(_) => (),
+        }
+    }
+}
+
+/// Walk the values of `<string>`, `<string-array>` and `<plurals>` resources. Resources marked with `translatable="false"` are skipped.
+pub(super) fn walk(
+    s: &str,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+) -> Result<String, FormatError> {
+    let mut document = XmlDocument::parse(s)?;
+
+    let root = document.root_mut();
+
+    if !root.is("resources") {
+        return Err(FormatError::Malformed {
+            line:   root.line,
+            reason: String::from("the root element is not <resources>"),
+        });
+    }
+
+    for resource in root.elements_mut() {
+        if resource.attribute("translatable") == Some("false") {
+            continue;
+        }
+
+        let name = match resource.attribute("name") {
+            Some(name) => String::from(name),
+            None => continue,
+        };
+
+        match resource.local_name() {
+            "string" => {
+                let line = resource.line;
+
+                walk_value(resource, &name, line, f);
+            },
+            "string-array" | "plurals" => {
+                for (i, item) in resource.elements_mut().filter(|e| e.is("item")).enumerate() {
+                    let key = match item.attribute("quantity") {
+                        Some(quantity) => format!("{}[{}]", name, quantity),
+                        None => format!("{}[{}]", name, i),
+                    };
+
+                    let line = item.line;
+
+                    walk_value(item, &key, line, f);
+                }
+            },
+            _ => (),
+        }
+    }
+
+    Ok(document.to_xml_string())
+}
@@ -0,0 +1,210 @@
+use crate::FormatError;
+
+/// An open placeable.
+struct Frame {
+    /// Whether the placeable is a select expression, after its `->`.
+    select: bool,
+    /// Whether the scanner is in the text of a variant.
+    text:   bool,
+}
+
+/// Parse the start of an entry like `id =` or `-term =`, returning the identifier and the position after `=`.
+fn entry_start(line: &str) -> Option<(&str, usize)> {
+    let id_end = line
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_' || c == '-'))
+        .unwrap_or(line.len());
+
+    let id = &line[..id_end];
+
+    if !id.strip_prefix('-').unwrap_or(id).starts_with(|c: char| c.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let rest = line[id_end..].trim_start_matches([' ', '\t']).strip_prefix('=')?;
+
+    Some((id, line.len() - rest.len()))
+}
+
+#[inline]
+fn emit(
+    output: &mut String,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+    key: &str,
+    line: usize,
+    s: &str,
+    text: bool,
+) {
+    if text && !s.trim().is_empty() {
+        if let Some(new_s) = f(key, line, s) {
+            output.push_str(&new_s);
+            return;
+        }
+    }
+
+    output.push_str(s);
+}
+
+/// Scan a line of a pattern from `start`, passing text outside placeables and text of variants to the callback.
+fn scan_line(
+    line: &str,
+    start: usize,
+    stack: &mut Vec<Frame>,
+    key: &str,
+    line_number: usize,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+    output: &mut String,
+) {
+    let bytes = line.as_bytes();
+
+    let mut i = start;
+
+    // a variant key like `[one]` or `*[other]` starts a line
+    if let Some(frame) = stack.last_mut() {
+        if frame.select {
+            let rest = line[i..].trim_start();
+            let variant = rest.strip_prefix('*').unwrap_or(rest);
+
+            if let Some(p) = variant.strip_prefix('[').and_then(|v| v.find(']')) {
+                i = line.len() - variant.len() + p + 2;
+
+                frame.text = true;
+            }
+        }
+    }
+
+    output.push_str(&line[start..i]);
+
+    let mut segment_start = i;
+
+    while i < bytes.len() {
+        let text = stack.last().map_or(true, |frame| frame.text);
+
+        match bytes[i] {
+            b'{' => {
+                emit(output, f, key, line_number, &line[segment_start..i], text);
+                output.push('{');
+
+                stack.push(Frame {
+                    select: false, text: false
+                });
+
+                i += 1;
+                segment_start = i;
+            },
+            b'}' if !stack.is_empty() => {
+                emit(output, f, key, line_number, &line[segment_start..i], text);
+                output.push('}');
+
+                stack.pop();
+
+                i += 1;
+                segment_start = i;
+            },
+            b'"' if !text => {
+                i += 1;
+
+                while i < bytes.len() {
+                    match bytes[i] {
+                        b'\\' => i += 2,
+                        b'"' => {
+                            i += 1;
+                            break;
+                        },
+                        _ => i += 1,
+                    }
+                }
+            },
+            b'-' if !text && bytes.get(i + 1) == Some(&b'>') => {
+                stack.last_mut().unwrap().select = true;
+
+                i += 2;
+            },
+            _ => i += 1,
+        }
+    }
+
+    let text = stack.last().map_or(true, |frame| frame.text);
+
+    emit(output, f, key, line_number, &line[segment_start.min(line.len())..], text);
+}
+
+/// Walk the text of messages, terms and attributes in a Fluent file. Placeables, selectors and variant keys are left untouched, while the text of variants is walked.
+pub(super) fn walk(
+    s: &str,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+) -> Result<String, FormatError> {
+    let mut output = String::with_capacity(s.len());
+
+    let mut stack: Vec<Frame> = Vec::new();
+
+    // the identifier of the current message or term
+    let mut id: Option<String> = None;
+    // the key of the current pattern, which is the identifier or `id.attribute`
+    let mut key = String::new();
+
+    let mut line_number = 0;
+
+    for raw_line in s.split_inclusive('\n') {
+        line_number += 1;
+
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let newline = &raw_line[line.len()..];
+
+        let start = if !stack.is_empty() {
+            0
+        } else if line.trim().is_empty() {
+            output.push_str(raw_line);
+            continue;
+        } else if !line.starts_with([' ', '\t']) {
+            // comments and junk end the current entry too
+            match entry_start(line) {
+                Some((entry_id, start)) => {
+                    id = Some(String::from(entry_id));
+                    key = String::from(entry_id);
+
+                    start
+                },
+                None => {
+                    id = None;
+
+                    output.push_str(raw_line);
+                    continue;
+                },
+            }
+        } else {
+            let id = match &id {
+                Some(id) => id,
+                None => {
+                    output.push_str(raw_line);
+                    continue;
+                },
+            };
+
+            let trimmed = line.trim_start();
+
+            match trimmed.strip_prefix('.').and_then(entry_start) {
+                Some((attribute, offset)) => {
+                    key = format!("{}.{}", id, attribute);
+
+                    line.len() - trimmed.len() + 1 + offset
+                },
+                None => line.len() - trimmed.len(),
+            }
+        };
+
+        output.push_str(&line[..start]);
+
+        scan_line(line, start, &mut stack, &key, line_number, f, &mut output);
+
+        output.push_str(newline);
+    }
+
+    if !stack.is_empty() {
+        return Err(FormatError::Malformed {
+            line:   line_number,
+            reason: String::from("a placeable is not closed"),
+        });
+    }
+
+    Ok(output)
+}
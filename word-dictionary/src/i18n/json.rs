@@ -0,0 +1,230 @@
+use std::{fmt::Write, str::Chars};
+
+use crate::FormatError;
+
+enum Frame {
+    Object { key: Option<String>, expecting_key: bool },
+    Array { index: usize },
+}
+
+fn path(stack: &[Frame]) -> String {
+    let mut path = String::new();
+
+    for frame in stack {
+        match frame {
+            Frame::Object {
+                key: Some(key), ..
+            } => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+
+                path.push_str(key);
+            },
+            Frame::Object {
+                key: None, ..
+            } => (),
+            Frame::Array {
+                index,
+            } => write!(path, "[{}]", index).unwrap(),
+        }
+    }
+
+    path
+}
+
+#[inline]
+fn read_code_unit(chars: &mut Chars) -> Option<u16> {
+    let hex: String = chars.take(4).collect();
+
+    u16::from_str_radix(&hex, 16).ok()
+}
+
+/// Decode the content of a JSON string literal.
+fn unescape(raw: &str, line: usize) -> Result<String, FormatError> {
+    let malformed = || FormatError::Malformed {
+        line,
+        reason: String::from("a string has a bad escape sequence"),
+    };
+
+    let mut output = String::with_capacity(raw.len());
+
+    let mut chars = raw.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        match chars.next().ok_or_else(malformed)? {
+            'n' => output.push('\n'),
+            't' => output.push('\t'),
+            'r' => output.push('\r'),
+            'b' => output.push('\u{8}'),
+            'f' => output.push('\u{C}'),
+            'u' => {
+                let high = read_code_unit(&mut chars).ok_or_else(malformed)?;
+
+                let c = if (0xD800..0xDC00).contains(&high) {
+                    if !chars.as_str().starts_with("\\u") {
+                        return Err(malformed());
+                    }
+
+                    chars.nth(1);
+
+                    let low = read_code_unit(&mut chars).ok_or_else(malformed)?;
+
+                    char::decode_utf16([high, low]).next().unwrap().map_err(|_| malformed())?
+                } else {
+                    char::from_u32(high as u32).ok_or_else(malformed)?
+                };
+
+                output.push(c);
+            },
+            c => output.push(c),
+        }
+    }
+
+    Ok(output)
+}
+
+fn escape(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            c if (c as u32) < 0x20 => write!(output, "\\u{:04x}", c as u32).unwrap(),
+            c => output.push(c),
+        }
+    }
+
+    output
+}
+
+/// Walk the string values of a JSON document. Everything else is copied as it is.
+pub(super) fn walk(
+    s: &str,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+) -> Result<String, FormatError> {
+    let mut output = String::with_capacity(s.len());
+
+    let mut stack: Vec<Frame> = Vec::new();
+
+    let mut line = 1;
+    let mut i = 0;
+
+    let bytes = s.as_bytes();
+
+    while i < bytes.len() {
+        let b = bytes[i];
+
+        match b {
+            b'"' => {
+                let start = i;
+                let start_line = line;
+
+                i += 1;
+
+                loop {
+                    match bytes.get(i) {
+                        Some(b'\\') => i += 2,
+                        Some(b'"') => break,
+                        Some(b'\n') | None => {
+                            return Err(FormatError::Malformed {
+                                line:   start_line,
+                                reason: String::from("a string is not closed"),
+                            })
+                        },
+                        Some(_) => i += 1,
+                    }
+                }
+
+                i += 1;
+
+                let raw = &s[start..i];
+
+                let content = unescape(&raw[1..raw.len() - 1], start_line)?;
+
+                match stack.last_mut() {
+                    Some(Frame::Object {
+                        key,
+                        expecting_key: expecting_key @ true,
+                    }) => {
+                        *key = Some(content);
+                        *expecting_key = false;
+
+                        output.push_str(raw);
+                    },
+                    _ => match f(&path(&stack), start_line, &content) {
+                        Some(new_content) => {
+                            output.push('"');
+                            output.push_str(&escape(&new_content));
+                            output.push('"');
+                        },
+                        None => output.push_str(raw),
+                    },
+                }
+
+                continue;
+            },
+            b'{' => stack.push(Frame::Object {
+                key: None, expecting_key: true
+            }),
+            b'[' => stack.push(Frame::Array {
+                index: 0
+            }),
+            b'}' | b']' => {
+                // the closer has to match the innermost opener
+                let expected = match stack.pop() {
+                    Some(Frame::Object {
+                        ..
+                    }) => Some(b'}'),
+                    Some(Frame::Array {
+                        ..
+                    }) => Some(b']'),
+                    None => None,
+                };
+
+                if expected != Some(b) {
+                    return Err(FormatError::Malformed {
+                        line,
+                        reason: format!("unexpected {:?}", b as char),
+                    });
+                }
+            },
+            b',' => match stack.last_mut() {
+                Some(Frame::Object {
+                    expecting_key, ..
+                }) => *expecting_key = true,
+                Some(Frame::Array {
+                    index,
+                }) => *index += 1,
+                None => (),
+            },
+            b'\n' => line += 1,
+            _ => (),
+        }
+
+        // copy a whole character
+        let len = s[i..].chars().next().unwrap().len_utf8();
+
+        output.push_str(&s[i..i + len]);
+
+        i += len;
+    }
+
+    if !stack.is_empty() {
+        return Err(FormatError::Malformed {
+            line,
+            reason: String::from("an object or an array is not closed"),
+        });
+    }
+
+    Ok(output)
+}
@@ -0,0 +1,295 @@
+mod android;
+mod fluent;
+mod json;
+mod yaml;
+
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    ops::Range,
+};
+
+use crate::{Dictionary, FormatError, TermIssue};
+
+/// Application i18n resource formats.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ResourceFormat {
+    /// Nested JSON message catalogs. Keys are joined by `.`, and array elements are keyed like `[0]`.
+    Json,
+    /// Nested YAML message catalogs, in the block style used by Rails and similar frameworks.
+    Yaml,
+    /// Project Fluent `.ftl` files. Attributes are keyed like `message.attribute`.
+    Fluent,
+    /// Android `strings.xml` resources. Items of string arrays and plurals are keyed like `name[0]` and `name[one]`.
+    AndroidStrings,
+}
+
+/// A message value in a resource file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ResourceMessage {
+    pub key:   String,
+    /// The line where the value starts.
+    pub line:  usize,
+    pub value: String,
+}
+
+/// An issue found in a translated resource file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ResourceIssue {
+    pub key:   String,
+    /// The line of the value in the source resource file.
+    pub line:  usize,
+    pub issue: TermIssue,
+}
+
+/// Walk the message values of a resource file. The callback gets the key, the line and the value, and returns a new value to replace the old one. The rewritten file is returned.
+type Walker =
+    fn(&str, &mut dyn FnMut(&str, usize, &str) -> Option<String>) -> Result<String, FormatError>;
+
+impl ResourceFormat {
+    #[inline]
+    fn walker(self) -> Walker {
+        match self {
+            ResourceFormat::Json => json::walk,
+            ResourceFormat::Yaml => yaml::walk,
+            ResourceFormat::Fluent => fluent::walk,
+            ResourceFormat::AndroidStrings => android::walk,
+        }
+    }
+}
+
+/// Split a message into ranges of text and ranges of placeholders or markup. Each range comes with whether it is protected.
+///
+/// Protected are `{...}` placeables (including nested ones), printf-style conversions like `%s` and `%1$d`, variables like `$var` and `${var}`, and tags like `<b>`.
+pub(crate) fn message_segments(s: &str) -> Vec<(Range<usize>, bool)> {
+    let bytes = s.as_bytes();
+
+    let mut segments: Vec<(Range<usize>, bool)> = Vec::new();
+
+    let mut push = |range: Range<usize>, protected: bool| {
+        if range.is_empty() {
+            return;
+        }
+
+        match segments.last_mut() {
+            Some((last, last_protected)) if *last_protected == protected => last.end = range.end,
+            _ => segments.push((range, protected)),
+        }
+    };
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let protected_len = match bytes[i] {
+            b'{' => {
+                let mut depth = 0;
+
+                bytes[i..]
+                    .iter()
+                    .position(|&b| {
+                        match b {
+                            b'{' => depth += 1,
+                            b'}' => depth -= 1,
+                            _ => (),
+                        }
+
+                        depth == 0
+                    })
+                    .map(|p| p + 1)
+            },
+            b'%' => printf_len(&bytes[i..]),
+            b'$' => match bytes.get(i + 1) {
+                Some(b'{') => bytes[i..].iter().position(|&b| b == b'}').map(|p| p + 1),
+                Some(b) if b.is_ascii_alphabetic() || *b == b'_' => Some(
+                    1 + bytes[i + 1..]
+                        .iter()
+                        .position(|b| !(b.is_ascii_alphanumeric() || *b == b'_'))
+                        .unwrap_or(bytes.len() - i - 1),
+                ),
+                _ => None,
+            },
+            b'<' => match bytes.get(i + 1) {
+                Some(b) if b.is_ascii_alphabetic() || *b == b'/' || *b == b'!' => {
+                    bytes[i..].iter().position(|&b| b == b'>').map(|p| p + 1)
+                },
+                _ => None,
+            },
+            _ => None,
+        };
+
+        match protected_len {
+            Some(len) => {
+                push(i..i + len, true);
+
+                i += len;
+            },
+            None => {
+                let len = s[i..].chars().next().unwrap().len_utf8();
+
+                push(i..i + len, false);
+
+                i += len;
+            },
+        }
+    }
+
+    segments
+}
+
+/// Get the length of a printf-style conversion like `%s`, `%1$s`, `%-5.2f` or `%@`.
+fn printf_len(bytes: &[u8]) -> Option<usize> {
+    let mut i = 1;
+
+    let digits = |i: usize| bytes[i..].iter().take_while(|b| b.is_ascii_digit()).count();
+
+    // argument index
+    let n = digits(i);
+
+    if n > 0 && bytes.get(i + n) == Some(&b'$') {
+        i += n + 1;
+    }
+
+    // flags, width and precision
+    i += bytes[i..].iter().take_while(|b| b"-+ #0".contains(b)).count();
+    i += digits(i);
+
+    if bytes.get(i) == Some(&b'.') {
+        i += 1 + digits(i + 1);
+    }
+
+    // length modifiers
+    i += bytes[i..].iter().take_while(|b| b"hlLqjzt".contains(b)).count();
+
+    match bytes.get(i) {
+        Some(b) if b"diouxXeEfFgGaAcspn@%".contains(b) => Some(i + 1),
+        _ => None,
+    }
+}
+
+/// Remove placeholders and markup from a message.
+#[inline]
+fn strip_placeholders(s: &str) -> String {
+    message_segments(s)
+        .into_iter()
+        .map(|(range, protected)| if protected { " " } else { &s[range] })
+        .collect()
+}
+
+impl Dictionary {
+    /// Transform a message, leaving placeholders and markup untouched. See `transform`.
+    pub fn transform_message<S: AsRef<str>>(&self, s: S) -> String {
+        let s = s.as_ref();
+
+        message_segments(s)
+            .into_iter()
+            .map(
+                |(range, protected)| {
+                    if protected {
+                        String::from(&s[range])
+                    } else {
+                        self.transform(&s[range])
+                    }
+                },
+            )
+            .collect()
+    }
+
+    /// Transform the message values in a resource file. Keys, placeholders, markup, comments and layout are kept. Return the count of changed values.
+    pub fn transform_resource<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        format: ResourceFormat,
+    ) -> Result<usize, FormatError> {
+        let mut s = String::new();
+
+        reader.read_to_string(&mut s)?;
+
+        let mut count = 0;
+        let mut last_key = String::new();
+
+        let output = format.walker()(&s, &mut |key, _, value| {
+            let transformed = self.transform_message(value);
+
+            if transformed != value {
+                // a value can be walked in several parts
+                if count == 0 || key != last_key {
+                    count += 1;
+                    last_key = String::from(key);
+                }
+
+                Some(transformed)
+            } else {
+                None
+            }
+        })?;
+
+        writer.write_all(output.as_bytes())?;
+
+        Ok(count)
+    }
+
+    /// Check the terms of every message in a translated resource file against the message with the same key in a source resource file. Placeholders and markup are ignored. See `check_terms`.
+    pub fn check_resource<S: Read, T: Read>(
+        &self,
+        source: S,
+        target: T,
+        format: ResourceFormat,
+    ) -> Result<Vec<ResourceIssue>, FormatError> {
+        let source = read_resource(source, format)?;
+
+        let target: HashMap<String, String> =
+            read_resource(target, format)?.into_iter().map(|m| (m.key, m.value)).collect();
+
+        let mut issues = Vec::new();
+
+        for message in source {
+            let target_value = match target.get(&message.key) {
+                Some(target_value) => target_value,
+                None => continue,
+            };
+
+            for issue in self
+                .check_terms(strip_placeholders(&message.value), strip_placeholders(target_value))
+            {
+                issues.push(ResourceIssue {
+                    key: message.key.clone(),
+                    line: message.line,
+                    issue,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
+
+/// Read the message values of a resource file. Values of the same key are joined by a space.
+pub fn read_resource<R: Read>(
+    mut reader: R,
+    format: ResourceFormat,
+) -> Result<Vec<ResourceMessage>, FormatError> {
+    let mut s = String::new();
+
+    reader.read_to_string(&mut s)?;
+
+    let mut messages: Vec<ResourceMessage> = Vec::new();
+
+    format.walker()(&s, &mut |key, line, value| {
+        match messages.last_mut() {
+            Some(last) if last.key == key => {
+                last.value.push(' ');
+                last.value.push_str(value);
+            },
+            _ => messages.push(ResourceMessage {
+                key: String::from(key),
+                line,
+                value: String::from(value),
+            }),
+        }
+
+        None
+    })?;
+
+    Ok(messages)
+}
@@ -0,0 +1,373 @@
+use crate::FormatError;
+
+/// Find the `:` which separates a key from its value, skipping quoted keys.
+fn find_key_separator(s: &str) -> Option<usize> {
+    let bytes = s.as_bytes();
+
+    let mut i = 0;
+
+    if let Some(&q @ (b'"' | b'\'')) = bytes.first() {
+        i = 1 + bytes[1..].iter().position(|&b| b == q)? + 1;
+    }
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b':' if i + 1 == bytes.len() || bytes[i + 1] == b' ' || bytes[i + 1] == b'\t' => {
+                return Some(i)
+            },
+            b'#' if i > 0 && bytes[i - 1] == b' ' => return None,
+            _ => (),
+        }
+
+        i += 1;
+    }
+
+    None
+}
+
+#[inline]
+fn unquote_key(key: &str) -> &str {
+    let key = key.trim();
+
+    if key.len() >= 2
+        && ((key.starts_with('"') && key.ends_with('"'))
+            || (key.starts_with('\'') && key.ends_with('\'')))
+    {
+        &key[1..key.len() - 1]
+    } else {
+        key
+    }
+}
+
+/// Whether a plain scalar has to be quoted.
+#[inline]
+fn needs_quotes(s: &str) -> bool {
+    s.contains(": ")
+        || s.contains(" #")
+        || s.ends_with(':')
+        || s.starts_with(|c: char| "-?:,[]{}#&*!|>'\"%@`".contains(c))
+}
+
+/// Decode the escape sequences of a double-quoted scalar. Return `None` if an escape sequence is not supported.
+fn unescape_double_quoted(s: &str) -> Option<String> {
+    let mut output = String::with_capacity(s.len());
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+
+        let c = match chars.next()? {
+            '0' => '\0',
+            'a' => '\x07',
+            'b' => '\x08',
+            't' | '\t' => '\t',
+            'n' => '\n',
+            'v' => '\x0B',
+            'f' => '\x0C',
+            'r' => '\r',
+            'e' => '\x1B',
+            'N' => '\u{85}',
+            '_' => '\u{A0}',
+            'L' => '\u{2028}',
+            'P' => '\u{2029}',
+            'x' => unescape_hex(&mut chars, 2)?,
+            'u' => unescape_hex(&mut chars, 4)?,
+            'U' => unescape_hex(&mut chars, 8)?,
+            c @ (' ' | '"' | '/' | '\\') => c,
+            _ => return None,
+        };
+
+        output.push(c);
+    }
+
+    Some(output)
+}
+
+#[inline]
+fn unescape_hex(chars: &mut std::str::Chars, len: usize) -> Option<char> {
+    let digits: String = chars.take(len).collect();
+
+    if digits.len() != len {
+        return None;
+    }
+
+    u32::from_str_radix(&digits, 16).ok().and_then(char::from_u32)
+}
+
+fn escape_double_quoted(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\\' => output.push_str("\\\\"),
+            '"' => output.push_str("\\\""),
+            '\n' => output.push_str("\\n"),
+            '\r' => output.push_str("\\r"),
+            '\t' => output.push_str("\\t"),
+            c if c.is_control() => output.push_str(&format!("\\u{:04X}", c as u32)),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+enum Scalar<'a> {
+    Plain(&'a str),
+    SingleQuoted(&'a str),
+    DoubleQuoted(&'a str),
+    /// A block scalar header like `|`, `>-` or `|2`.
+    Block,
+    /// Nothing, a flow collection, an anchor, an alias or a tag. They are left untouched.
+    Other,
+}
+
+/// Split a value into the scalar and the trailing comment.
+fn parse_scalar(value: &str) -> (Scalar<'_>, &str) {
+    let trimmed = value.trim_end();
+
+    match value.as_bytes().first() {
+        None => (Scalar::Other, ""),
+        Some(b'"') => {
+            let bytes = value.as_bytes();
+            let mut i = 1;
+
+            while i < bytes.len() {
+                match bytes[i] {
+                    b'\\' => i += 2,
+                    b'"' => return (Scalar::DoubleQuoted(&value[1..i]), &value[i + 1..]),
+                    _ => i += 1,
+                }
+            }
+
+            (Scalar::Other, "")
+        },
+        Some(b'\'') => {
+            let bytes = value.as_bytes();
+            let mut i = 1;
+
+            while i < bytes.len() {
+                if bytes[i] == b'\'' {
+                    if bytes.get(i + 1) == Some(&b'\'') {
+                        i += 2;
+                        continue;
+                    }
+
+                    return (Scalar::SingleQuoted(&value[1..i]), &value[i + 1..]);
+                }
+
+                i += 1;
+            }
+
+            (Scalar::Other, "")
+        },
+        Some(b'|' | b'>') => (Scalar::Block, ""),
+        Some(b'[' | b'{' | b'&' | b'*' | b'!') => (Scalar::Other, ""),
+        Some(_) => {
+            let end = trimmed.find(" #").unwrap_or(trimmed.len());
+
+            (Scalar::Plain(trimmed[..end].trim_end()), &value[end..])
+        },
+    }
+}
+
+#[inline]
+fn indent_of(line: &str) -> usize {
+    line.len() - line.trim_start_matches(' ').len()
+}
+
+/// Walk the scalar values of a block-style YAML document. Flow collections, anchors, aliases and tags are left untouched.
+pub(super) fn walk(
+    s: &str,
+    f: &mut dyn FnMut(&str, usize, &str) -> Option<String>,
+) -> Result<String, FormatError> {
+    let mut output = String::with_capacity(s.len());
+
+    // (indent, key) of the ancestors; list items are keyed like `[0]`
+    let mut stack: Vec<(usize, String)> = Vec::new();
+    // the next index of a list at an indent
+    let mut list_indexes: Vec<(usize, usize)> = Vec::new();
+    // (indent of the key, key path) of the block scalar being read
+    let mut block: Option<(usize, String)> = None;
+
+    let path = |stack: &[(usize, String)], key: &str| {
+        let mut path = String::new();
+
+        for k in stack.iter().map(|(_, k)| k.as_str()).chain(std::iter::once(key)) {
+            if !path.is_empty() && !k.starts_with('[') {
+                path.push('.');
+            }
+
+            path.push_str(k);
+        }
+
+        path
+    };
+
+    for (i, raw_line) in s.split_inclusive('\n').enumerate() {
+        let line_number = i + 1;
+
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+        let newline = &raw_line[line.len()..];
+
+        let indent = indent_of(line);
+        let content = &line[indent..];
+
+        if let Some((block_indent, key)) = &block {
+            if content.is_empty() || indent > *block_indent {
+                match f(key, line_number, content) {
+                    Some(new_content) if !content.is_empty() => {
+                        output.push_str(&line[..indent]);
+                        output.push_str(&new_content);
+                        output.push_str(newline);
+                    },
+                    _ => output.push_str(raw_line),
+                }
+
+                continue;
+            }
+
+            block = None;
+        }
+
+        if content.is_empty() || content.starts_with('#') || content == "---" || content == "..." {
+            output.push_str(raw_line);
+            continue;
+        }
+
+        if content.starts_with('\t') {
+            return Err(FormatError::Malformed {
+                line:   line_number,
+                reason: String::from("tabs cannot be used for indentation"),
+            });
+        }
+
+        // a list item like `- value` or `- key: value`
+        let mut indent = indent;
+        let mut content = content;
+        let mut item_key = None;
+
+        while content == "-" || content.starts_with("- ") {
+            while stack.last().map_or(false, |(i, _)| *i >= indent) {
+                stack.pop();
+            }
+
+            list_indexes.retain(|(i, _)| *i <= indent);
+
+            let index = match list_indexes.iter_mut().find(|(i, _)| *i == indent) {
+                Some((_, index)) => {
+                    *index += 1;
+                    *index
+                },
+                None => {
+                    list_indexes.push((indent, 0));
+                    0
+                },
+            };
+
+            let key = format!("[{}]", index);
+
+            let rest = content[1..].trim_start_matches(' ');
+
+            let next_indent = indent + (content.len() - rest.len());
+
+            if rest.is_empty() || find_key_separator(rest).is_some() || rest.starts_with("- ") {
+                stack.push((indent, key));
+            } else {
+                item_key = Some(key);
+            }
+
+            indent = next_indent;
+            content = rest;
+        }
+
+        let value_start;
+        let key;
+
+        match item_key {
+            Some(item_key) => {
+                key = path(&stack, &item_key);
+                value_start = line.len() - content.len();
+            },
+            None => {
+                while stack.last().map_or(false, |(i, _)| *i >= indent) {
+                    stack.pop();
+                }
+
+                list_indexes.retain(|(i, _)| *i <= indent);
+
+                if content.is_empty() {
+                    output.push_str(raw_line);
+                    continue;
+                }
+
+                let separator = match find_key_separator(content) {
+                    Some(separator) => separator,
+                    None => {
+                        // a continuation of a multi-line plain scalar, which is not supported
+                        output.push_str(raw_line);
+                        continue;
+                    },
+                };
+
+                let k = unquote_key(&content[..separator]);
+
+                let value = &content[separator + 1..];
+                let value_trimmed = value.trim_start();
+
+                if value_trimmed.is_empty() || value_trimmed.starts_with('#') {
+                    stack.push((indent, String::from(k)));
+
+                    output.push_str(raw_line);
+                    continue;
+                }
+
+                key = path(&stack, k);
+                value_start = line.len() - value_trimmed.len();
+            },
+        }
+
+        let value = &line[value_start..];
+
+        let (scalar, rest) = parse_scalar(value);
+
+        let new_value = match scalar {
+            Scalar::Plain(v) => f(&key, line_number, v).map(|new_v| {
+                if needs_quotes(&new_v) {
+                    format!("\"{}\"", escape_double_quoted(&new_v))
+                } else {
+                    new_v
+                }
+            }),
+            Scalar::SingleQuoted(v) => {
+                let unquoted = v.replace("''", "'");
+
+                f(&key, line_number, &unquoted)
+                    .map(|new_v| format!("'{}'", new_v.replace('\'', "''")))
+            },
+            Scalar::DoubleQuoted(v) => unescape_double_quoted(v)
+                .and_then(|unescaped| f(&key, line_number, &unescaped))
+                .map(|new_v| format!("\"{}\"", escape_double_quoted(&new_v))),
+            Scalar::Block => {
+                block = Some((indent, key));
+                None
+            },
+            Scalar::Other => None,
+        };
+
+        match new_value {
+            Some(new_value) => {
+                output.push_str(&line[..value_start]);
+                output.push_str(&new_value);
+                output.push_str(rest);
+                output.push_str(newline);
+            },
+            None => output.push_str(raw_line),
+        }
+    }
+
+    Ok(output)
+}
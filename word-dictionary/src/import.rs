@@ -0,0 +1,174 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{is_bad_word, Dictionary, Entry, OperationKind, WriteError};
+
+/// How to treat imported entries whose left words already exist in the dictionary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImportMode {
+    /// Append the imported current right word to the existing entry, like `add_edit` does.
+    AddEdit,
+    /// Leave the existing entry untouched and report the difference as a conflict.
+    KeepExisting,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ConflictReason {
+    BadLeftString,
+    BadRightString { right_string: String },
+    Same,
+    Duplicated,
+    Changed { current_right_string: String, new_right_string: String },
+}
+
+/// An imported entry which is not applied to the dictionary.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ImportConflict {
+    /// The position of the entry in the imported sequence, or a line number if the format has lines.
+    pub position:    usize,
+    pub left_string: String,
+    pub reason:      ConflictReason,
+}
+
+impl Display for ImportConflict {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!("conflict at {}, ", self.position))?;
+
+        match &self.reason {
+            ConflictReason::BadLeftString => {
+                f.write_fmt(format_args!("the left string {:?} is not correct", self.left_string))
+            },
+            ConflictReason::BadRightString {
+                right_string,
+            } => f.write_fmt(format_args!("the right string {:?} is not correct", right_string)),
+            ConflictReason::Same => f.write_fmt(format_args!(
+                "the left string {:?} is equal to its right string",
+                self.left_string
+            )),
+            ConflictReason::Duplicated => f.write_fmt(format_args!(
+                "the pair of the left string {:?} and its right string is duplicated",
+                self.left_string
+            )),
+            ConflictReason::Changed {
+                current_right_string,
+                new_right_string,
+            } => f.write_fmt(format_args!(
+                "the left string {:?} is mapped to {:?} rather than {:?}",
+                self.left_string, current_right_string, new_right_string
+            )),
+        }
+    }
+}
+
+/// The result of an import.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct ImportReport {
+    /// The count of new entries.
+    pub added:     usize,
+    /// The count of existing entries which got a new right word.
+    pub updated:   usize,
+    /// Entries which are not applied.
+    pub conflicts: Vec<ImportConflict>,
+}
+
+impl Dictionary {
+    /// Import entries with the same rules as `add_edit`. The dictionary file is written once after all entries are applied.
+    ///
+    /// The `position` of each conflict is the index of the entry in `entries`.
+    #[inline]
+    pub fn import_entries<I: IntoIterator<Item = Entry>>(
+        &mut self,
+        entries: I,
+        mode: ImportMode,
+    ) -> Result<ImportReport, WriteError> {
+        self.import_entries_with_positions(entries.into_iter().enumerate(), mode)
+    }
+
+    pub(crate) fn import_entries_with_positions<I: IntoIterator<Item = (usize, Entry)>>(
+        &mut self,
+        entries: I,
+        mode: ImportMode,
+    ) -> Result<ImportReport, WriteError> {
+        let mut report = ImportReport::default();
+
+        let snapshot = self.snapshot();
+
+        for (position, entry) in entries {
+            let left = entry.left.trim();
+
+            let conflict = |reason: ConflictReason| ImportConflict {
+                position,
+                left_string: String::from(left),
+                reason,
+            };
+
+            if left.is_empty() {
+                report.conflicts.push(conflict(ConflictReason::BadLeftString));
+                continue;
+            }
+
+            let right: Vec<&str> = entry.right.iter().map(|s| s.trim()).collect();
+
+            let right_string = match right.last() {
+                Some(right_string) => *right_string,
+                None => {
+                    report.conflicts.push(conflict(ConflictReason::BadRightString {
+                        right_string: String::new(),
+                    }));
+                    continue;
+                },
+            };
+
+            if let Some(s) = right.iter().find(|s| s.is_empty() || is_bad_word(s)) {
+                report.conflicts.push(conflict(ConflictReason::BadRightString {
+                    right_string: String::from(*s),
+                }));
+                continue;
+            }
+
+            if right.contains(&left) {
+                report.conflicts.push(conflict(ConflictReason::Same));
+                continue;
+            }
+
+            match self.check_add_edit(left, right_string) {
+                Ok(Some(index)) => match mode {
+                    ImportMode::AddEdit => {
+                        self.right[index].push(String::from(right_string));
+
+                        report.updated += 1;
+                    },
+                    ImportMode::KeepExisting => {
+                        report.conflicts.push(conflict(ConflictReason::Changed {
+                            current_right_string: String::from(self.get_right(index).unwrap()),
+                            new_right_string:     String::from(right_string),
+                        }));
+                    },
+                },
+                Ok(None) => {
+                    self.left.push(String::from(left));
+                    self.right.push(right.into_iter().map(String::from).collect());
+
+                    report.added += 1;
+                },
+                Err(WriteError::BadLeftString) => {
+                    report.conflicts.push(conflict(ConflictReason::BadLeftString));
+                },
+                Err(WriteError::Duplicated) => {
+                    report.conflicts.push(conflict(ConflictReason::Duplicated));
+                },
+                Err(err) => unreachable!("{:?}", err),
+            }
+        }
+
+        if report.added > 0 || report.updated > 0 {
+            self.rebuild_prefix_index();
+
+            self.record(OperationKind::Import, snapshot);
+
+            self.write_data()?;
+        }
+
+        Ok(report)
+    }
+}
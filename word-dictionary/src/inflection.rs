@@ -0,0 +1,118 @@
+use std::ops::Range;
+
+/// A simple English morphological variant of a left word.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Inflection {
+    /// A plural ending with `-s` or `-es`, like `Dragonborns`.
+    Plural,
+    /// A possessive ending with `'s`, or only `'` after a final `s`, like `Draugr's`.
+    Possessive,
+    /// A plural possessive ending with `s'` or `es'`, like `Dragonborns'`.
+    PluralPossessive,
+    /// A part of a hyphenated compound, like `Thalmor-controlled`.
+    Compound,
+}
+
+#[inline]
+fn is_apostrophe(c: char) -> bool {
+    c == '\'' || c == '\u{2019}'
+}
+
+#[inline]
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// Whether the plural of a word takes `-es`.
+#[inline]
+fn takes_es(word: &str) -> bool {
+    let word = word.to_ascii_lowercase();
+
+    ["s", "x", "z", "ch", "sh"].iter().any(|e| word.ends_with(e))
+}
+
+/// Find an inflectional suffix right after a left word matched at `stem`. The suffix has to end at a word boundary. Only left words ending with an ASCII letter are inflected.
+pub(crate) fn find_suffix(s: &str, stem: Range<usize>, left: &str) -> Option<(usize, Inflection)> {
+    if !left.as_bytes().last().map_or(false, |b| b.is_ascii_alphabetic()) {
+        return None;
+    }
+
+    let rest = &s[stem.end..];
+
+    let plural_suffix = if takes_es(left) { "es" } else { "s" };
+
+    let mut candidates: Vec<(usize, Inflection)> = Vec::with_capacity(3);
+
+    // plural
+    if rest.len() >= plural_suffix.len()
+        && rest.as_bytes()[..plural_suffix.len()].eq_ignore_ascii_case(plural_suffix.as_bytes())
+    {
+        let len = plural_suffix.len();
+
+        match rest[len..].chars().next() {
+            Some(c) if is_apostrophe(c) => {
+                candidates.push((len + c.len_utf8(), Inflection::PluralPossessive))
+            },
+            _ => candidates.push((len, Inflection::Plural)),
+        }
+    }
+
+    // possessive
+    let mut chars = rest.chars();
+
+    if let Some(c) = chars.next().filter(|&c| is_apostrophe(c)) {
+        match chars.next() {
+            Some('s' | 'S') => candidates.push((c.len_utf8() + 1, Inflection::Possessive)),
+            _ if left.ends_with(['s', 'S']) => {
+                candidates.push((c.len_utf8(), Inflection::Possessive))
+            },
+            _ => (),
+        }
+    }
+
+    candidates.into_iter().find(|&(len, _)| !rest[len..].chars().next().map_or(false, is_word_char))
+}
+
+/// Whether a match is a part of a hyphenated compound.
+pub(crate) fn is_in_compound(s: &str, range: Range<usize>) -> bool {
+    let after = &s[range.end..];
+    let before = &s[..range.start];
+
+    after.strip_prefix('-').and_then(|a| a.chars().next()).map_or(false, is_word_char)
+        || before.strip_suffix('-').and_then(|b| b.chars().next_back()).map_or(false, is_word_char)
+}
+
+/// Make the plural of a right word. Right words not ending with an ASCII letter, like words in Chinese, are not inflected.
+fn pluralize(right: &str) -> String {
+    match right.chars().next_back() {
+        Some(c) if c.is_ascii_alphabetic() => {
+            let suffix = if takes_es(right) { "es" } else { "s" };
+
+            if c.is_ascii_uppercase() {
+                format!("{}{}", right, suffix.to_ascii_uppercase())
+            } else {
+                format!("{}{}", right, suffix)
+            }
+        },
+        _ => String::from(right),
+    }
+}
+
+/// Inflect a right word like the matched text.
+pub(crate) fn inflect(right: &str, inflection: Inflection, matched: &str) -> String {
+    match inflection {
+        Inflection::Plural => pluralize(right),
+        Inflection::Possessive | Inflection::PluralPossessive => {
+            let apostrophe = matched.rfind(is_apostrophe).unwrap();
+
+            let right = if inflection == Inflection::PluralPossessive {
+                pluralize(right)
+            } else {
+                String::from(right)
+            };
+
+            format!("{}{}", right, &matched[apostrophe..])
+        },
+        Inflection::Compound => String::from(right),
+    }
+}
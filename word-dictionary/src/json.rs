@@ -0,0 +1,68 @@
+use std::io::{self, Read, Write};
+
+use serde::{ser::SerializeSeq, Serialize, Serializer};
+
+use crate::{
+    Dictionary, DictionaryDiff, Entry, FormatError, ImportError, ImportMode, ImportReport,
+};
+
+impl From<serde_json::Error> for FormatError {
+    #[inline]
+    fn from(error: serde_json::Error) -> Self {
+        if error.is_io() {
+            FormatError::IOError(io::Error::from(error))
+        } else {
+            FormatError::Malformed {
+                line: error.line(), reason: error.to_string()
+            }
+        }
+    }
+}
+
+/// A dictionary is serialized as a sequence of its entries.
+impl Serialize for Dictionary {
+    #[inline]
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.count()))?;
+
+        for index in 0..self.count() {
+            seq.serialize_element(&self.get_entry(index).unwrap())?;
+        }
+
+        seq.end()
+    }
+}
+
+/// Read entries from a JSON array like `[{"left": "Alduin", "right": ["阿爾杜因", "奥杜因"]}]`.
+#[inline]
+pub fn read_json<R: Read>(reader: R) -> Result<Vec<Entry>, FormatError> {
+    Ok(serde_json::from_reader(reader)?)
+}
+
+impl Dictionary {
+    /// Export all entries to a JSON array.
+    #[inline]
+    pub fn export_json<W: Write>(&self, writer: W) -> Result<(), FormatError> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+
+    /// Import entries from a JSON array with the same rules as `add_edit`. The `position` of each conflict is the index in the array.
+    #[inline]
+    pub fn import_json<R: Read>(
+        &mut self,
+        reader: R,
+        mode: ImportMode,
+    ) -> Result<ImportReport, ImportError> {
+        let entries = read_json(reader)?;
+
+        Ok(self.import_entries(entries, mode)?)
+    }
+}
+
+impl DictionaryDiff {
+    /// Export the changes to JSON like `{"changes": [{"kind": "retranslated", "left": "Dwemer", "old_right": ["德魏默"], "new_right": ["德魏默", "德威默"]}]}`. The kind is one of `added`, `removed`, `retranslated` and `history_changed`.
+    #[inline]
+    pub fn export_json<W: Write>(&self, writer: W) -> Result<(), FormatError> {
+        Ok(serde_json::to_writer_pretty(writer, self)?)
+    }
+}
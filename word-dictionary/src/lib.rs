@@ -37,19 +37,80 @@ use std::{
     path::PathBuf,
 };
 
+mod completion;
+mod diff;
+mod duplicates;
+mod entry;
 mod errors;
-
+mod extract;
+mod i18n;
+mod import;
+mod inflection;
+#[cfg(feature = "serde")]
+mod json;
+mod markup;
+mod matcher;
+mod merge;
+mod phonetic;
+#[cfg(feature = "pinyin")]
+mod pinyin;
+mod po;
+#[cfg(feature = "regex")]
+mod regex_search;
+mod search;
+mod strings;
+mod subtitle;
+mod table;
+mod tbx;
+mod three_way;
+mod transform;
+mod undo;
+mod xliff;
+mod xml;
+
+pub use completion::*;
+pub use diff::*;
+pub use duplicates::*;
+pub use entry::*;
 pub use errors::*;
+pub use extract::*;
+pub use i18n::*;
+pub use import::*;
+pub use inflection::*;
+#[cfg(feature = "serde")]
+pub use json::*;
+pub use markup::*;
+pub use matcher::*;
+pub use merge::*;
+pub use phonetic::*;
+pub use po::*;
+#[cfg(feature = "regex")]
+pub use regex_search::*;
+pub use search::*;
+pub use strings::*;
+pub use subtitle::*;
+pub use table::*;
+pub use tbx::*;
+pub use three_way::*;
+pub use transform::*;
 use trim_in_place::TrimInPlace;
+pub use undo::*;
+pub use xliff::*;
 
 #[derive(Debug)]
 pub struct Dictionary {
     /// The path of the dictionary file.
-    path:  PathBuf,
+    path:          PathBuf,
     /// Left data.
-    left:  Vec<String>,
+    left:          Vec<String>,
     /// Right data.
-    right: Vec<Vec<String>>,
+    right:         Vec<Vec<String>>,
+    /// The matcher over left data and right data.
+    matcher:       Matcher,
+    /// The prefix index over left data and current right data.
+    prefix_index:  PrefixIndex,
+    /// The operations which can be undone or redone.
+    operation_log: OperationLog,
 }
 
 impl Dictionary {
@@ -57,7 +118,12 @@ impl Dictionary {
     #[inline]
     pub fn new<P: Into<PathBuf>>(path: P) -> Dictionary {
         Dictionary {
-            path: path.into(), left: Vec::new(), right: Vec::new()
+            path:          path.into(),
+            left:          Vec::new(),
+            right:         Vec::new(),
+            matcher:       Matcher::empty(),
+            prefix_index:  PrefixIndex::default(),
+            operation_log: OperationLog::default(),
         }
     }
 }
@@ -249,7 +315,20 @@ impl Dictionary {
 
 impl Dictionary {
     /// Read the dictionary from the dictionary file.
+    #[inline]
     pub fn read_data(&mut self) -> Result<(), ReadError> {
+        let result = self.read_file();
+
+        self.rebuild_matcher();
+        self.rebuild_prefix_index();
+
+        // the recorded operations may not match the file anymore
+        self.operation_log.clear();
+
+        result
+    }
+
+    fn read_file(&mut self) -> Result<(), ReadError> {
         let file = match File::open(&self.path) {
             Ok(file) => file,
             Err(err) if err.kind() == ErrorKind::NotFound => {
@@ -355,7 +434,18 @@ impl Dictionary {
 
 impl Dictionary {
     /// Write this dictionary to its dictionary file.
+    #[inline]
     pub fn write_data(&mut self) -> Result<(), WriteError> {
+        let result = self.write_file();
+
+        // entries may have been changed and sorted
+        self.rebuild_matcher();
+        self.refresh_entry_indices();
+
+        result
+    }
+
+    fn write_file(&mut self) -> Result<(), WriteError> {
         let mut file = File::create(&self.path)?;
 
         let size = self.count();
@@ -392,8 +482,12 @@ impl Dictionary {
     #[inline]
     pub fn delete(&mut self, index: usize) -> Result<bool, WriteError> {
         if index < self.count() {
-            self.left.remove(index);
-            self.right.remove(index);
+            let left = self.left.remove(index);
+            let right = self.right.remove(index);
+
+            self.prefix_index.remove(&left, right.last().unwrap());
+
+            self.record_edit(OperationKind::Delete, &left, Some(right), None);
 
             self.write_data()?;
 
@@ -412,9 +506,55 @@ impl Dictionary {
         let left = left.as_ref().trim();
         let right = right.as_ref().trim();
 
-        if left.contains("-->") || left.contains('=') {
+        let index = self.check_add_edit(left, right)?;
+
+        match index {
+            Some(index) => {
+                self.prefix_index.remove(&self.left[index], self.right[index].last().unwrap());
+                self.prefix_index.insert(&self.left[index], right);
+
+                let before = self.right[index].clone();
+
+                self.right.get_mut(index).unwrap().push(String::from(right));
+
+                let left = self.left[index].clone();
+                let after = self.right[index].clone();
+
+                self.record_edit(OperationKind::AddEdit, &left, Some(before), Some(after));
+
+                self.write_data()?;
+
+                Ok(false)
+            },
+            None => {
+                self.prefix_index.insert(left, right);
+
+                self.left.push(String::from(left));
+                self.right.push(vec![String::from(right)]);
+
+                self.record_edit(
+                    OperationKind::AddEdit,
+                    left,
+                    None,
+                    Some(vec![String::from(right)]),
+                );
+
+                self.write_data()?;
+
+                Ok(true)
+            },
+        }
+    }
+
+    /// Check whether a pair of trimmed words can be added or edited. Return the index of the existing left word if there is one.
+    pub(crate) fn check_add_edit(
+        &self,
+        left: &str,
+        right: &str,
+    ) -> Result<Option<usize>, WriteError> {
+        if is_bad_word(left) {
             Err(WriteError::BadLeftString)
-        } else if right.contains("-->") || right.contains('=') {
+        } else if is_bad_word(right) {
             Err(WriteError::BadRightString)
         } else if left == right {
             Err(WriteError::Same)
@@ -422,19 +562,16 @@ impl Dictionary {
             if self.get_right(index).unwrap() == right {
                 Err(WriteError::Duplicated)
             } else {
-                self.right.get_mut(index).unwrap().push(String::from(right));
-
-                self.write_data()?;
-
-                Ok(false)
+                Ok(Some(index))
             }
         } else {
-            self.left.push(String::from(left));
-            self.right.push(vec![String::from(right)]);
-
-            self.write_data()?;
-
-            Ok(true)
+            Ok(None)
         }
     }
 }
+
+/// Whether a word cannot be written in a line of the dictionary file, because it contains `-->`, `=` or a line break.
+#[inline]
+pub(crate) fn is_bad_word(s: &str) -> bool {
+    s.contains("-->") || s.contains(['=', '\n', '\r'])
+}
@@ -0,0 +1,358 @@
+use std::{collections::HashSet, ops::Range};
+
+/// The markup languages which a text can be written in for transformation.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum Markup {
+    /// Everything is text.
+    #[default]
+    PlainText,
+    /// Only text nodes are transformed. Tags (with their attribute values), comments, character references and the content of `<script>`, `<style>`, `<code>` and `<pre>` elements are skipped.
+    Html,
+    /// Code blocks, code spans, link targets, reference link labels, link reference definitions, URLs, raw HTML tags (with the content of the elements skipped in `Html`) and backslash escapes are skipped.
+    Markdown,
+}
+
+/// Elements whose content is not human-visible text.
+const SKIPPED_ELEMENTS: [&str; 4] = ["script", "style", "code", "pre"];
+
+impl Markup {
+    /// Split a text into ranges of text and ranges of markup. Each range comes with whether it is markup.
+    pub(crate) fn segments(self, s: &str) -> Vec<(Range<usize>, bool)> {
+        match self {
+            Markup::PlainText => vec![(0..s.len(), false)],
+            Markup::Html => html_segments(s),
+            Markup::Markdown => markdown_segments(s),
+        }
+    }
+}
+
+/// Append a range to segments, merging it with the last segment of the same kind.
+#[inline]
+fn push_segment(segments: &mut Vec<(Range<usize>, bool)>, range: Range<usize>, markup: bool) {
+    if range.is_empty() {
+        return;
+    }
+
+    match segments.last_mut() {
+        Some((last, last_markup)) if *last_markup == markup => last.end = range.end,
+        _ => segments.push((range, markup)),
+    }
+}
+
+/// Get the length of a character reference like `&amp;` or `&#39;` at `i`.
+#[inline]
+pub(crate) fn character_reference_len(bytes: &[u8], i: usize) -> Option<usize> {
+    bytes[i + 1..]
+        .iter()
+        .position(|b| !(b.is_ascii_alphanumeric() || *b == b'#'))
+        .filter(|&p| p > 0 && bytes[i + 1 + p] == b';')
+        .map(|p| p + 2)
+}
+
+/// Find the end of a tag starting at `i`, skipping quoted attribute values.
+fn tag_end(bytes: &[u8], i: usize) -> Option<usize> {
+    let mut quote = None;
+
+    for (j, &b) in bytes.iter().enumerate().skip(i + 1) {
+        match quote {
+            Some(q) => {
+                if b == q {
+                    quote = None;
+                }
+            },
+            None => match b {
+                b'"' | b'\'' => quote = Some(b),
+                b'>' => return Some(j + 1),
+                _ => (),
+            },
+        }
+    }
+
+    None
+}
+
+/// Find the end of HTML markup starting at `i`, which is a tag or a comment.
+fn html_markup_end(s: &str, i: usize) -> Option<usize> {
+    let bytes = s.as_bytes();
+
+    if s[i..].starts_with("<!--") {
+        return Some(s[i + 4..].find("-->").map_or(s.len(), |p| i + 4 + p + 3));
+    }
+
+    match bytes.get(i + 1) {
+        Some(b) if b.is_ascii_alphabetic() || b"/!?".contains(b) => tag_end(bytes, i),
+        _ => None,
+    }
+}
+
+/// Find the end of HTML markup starting at `i`. The content of skipped elements is included.
+fn html_skipped_end(s: &str, i: usize) -> Option<usize> {
+    let bytes = s.as_bytes();
+
+    html_markup_end(s, i).map(|end| {
+        let name_len = bytes[i + 1..end].iter().take_while(|b| b.is_ascii_alphanumeric()).count();
+        let name = &s[i + 1..i + 1 + name_len];
+
+        if !s[..end].ends_with("/>")
+            && SKIPPED_ELEMENTS.iter().any(|e| e.eq_ignore_ascii_case(name))
+        {
+            // skip to the end of the element
+            let close = format!("</{}", name.to_ascii_lowercase());
+
+            match s[end..].to_ascii_lowercase().find(&close) {
+                Some(p) => tag_end(bytes, end + p).unwrap_or(s.len()),
+                None => s.len(),
+            }
+        } else {
+            end
+        }
+    })
+}
+
+fn html_segments(s: &str) -> Vec<(Range<usize>, bool)> {
+    let bytes = s.as_bytes();
+
+    let mut segments = Vec::new();
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let markup_end = match bytes[i] {
+            b'<' => html_skipped_end(s, i),
+            b'&' => character_reference_len(bytes, i).map(|len| i + len),
+            _ => None,
+        };
+
+        match markup_end {
+            Some(end) => {
+                push_segment(&mut segments, i..end, true);
+
+                i = end;
+            },
+            None => {
+                let len = s[i..].chars().next().unwrap().len_utf8();
+
+                push_segment(&mut segments, i..i + len, false);
+
+                i += len;
+            },
+        }
+    }
+
+    segments
+}
+
+/// Get the fence of a fenced code block, which is the fence character and the length of the fence.
+#[inline]
+fn code_fence(line: &str) -> Option<(u8, usize)> {
+    let trimmed = line.trim_start_matches(' ');
+
+    if line.len() - trimmed.len() >= 4 {
+        return None;
+    }
+
+    let c = *trimmed.as_bytes().first().filter(|&&c| c == b'`' || c == b'~')?;
+
+    let len = trimmed.bytes().take_while(|&b| b == c).count();
+
+    if len >= 3 && !(c == b'`' && trimmed[len..].contains('`')) {
+        Some((c, len))
+    } else {
+        None
+    }
+}
+
+/// Get the label of a link reference definition like `[id]: https://example.com`. Return `None` if the line is not a definition.
+#[inline]
+fn link_reference_definition(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start_matches(' ');
+
+    if line.len() - trimmed.len() >= 4 || !trimmed.starts_with('[') {
+        return None;
+    }
+
+    trimmed.find("]:").filter(|&p| p > 1).map(|p| &trimmed[1..p])
+}
+
+/// Normalize a link label for matching, ignoring case and runs of whitespace.
+#[inline]
+fn normalize_link_label(label: &str) -> String {
+    label.split_whitespace().collect::<Vec<&str>>().join(" ").to_lowercase()
+}
+
+/// Find the end of a shortcut or collapsed reference link like `[id]` or `[id][]` at `i`, whose text is its label.
+fn shortcut_reference_end(s: &str, i: usize, labels: &HashSet<String>) -> Option<usize> {
+    if labels.is_empty() {
+        return None;
+    }
+
+    let bytes = s.as_bytes();
+
+    let close = i + 1 + bytes[i + 1..].iter().position(|&b| b == b']')?;
+
+    if !labels.contains(&normalize_link_label(&s[i + 1..close])) {
+        return None;
+    }
+
+    match bytes.get(close + 1) {
+        Some(b'[') if bytes.get(close + 2) == Some(&b']') => Some(close + 3),
+        // the text of an inline link or a full reference link is transformed
+        Some(b'(' | b'[') => None,
+        _ => Some(close + 1),
+    }
+}
+
+/// Split the inline content of a Markdown line. `labels` are the normalized labels of link reference definitions.
+fn markdown_inline_segments(
+    s: &str,
+    range: Range<usize>,
+    labels: &HashSet<String>,
+    segments: &mut Vec<(Range<usize>, bool)>,
+) {
+    let s = &s[..range.end];
+    let bytes = s.as_bytes();
+
+    let mut i = range.start;
+
+    while i < bytes.len() {
+        let markup_end = match bytes[i] {
+            b'`' => {
+                let len = bytes[i..].iter().take_while(|&&b| b == b'`').count();
+
+                // a code span ends with a backtick string of the same length
+                let mut j = i + len;
+                let mut end = None;
+
+                while j < bytes.len() {
+                    if bytes[j] == b'`' {
+                        let close_len = bytes[j..].iter().take_while(|&&b| b == b'`').count();
+
+                        if close_len == len {
+                            end = Some(j + len);
+                            break;
+                        }
+
+                        j += close_len;
+                    } else {
+                        j += 1;
+                    }
+                }
+
+                Some(end.unwrap_or(i + len))
+            },
+            b'\\' if bytes.get(i + 1).map_or(false, |b| b.is_ascii_punctuation()) => Some(i + 2),
+            b']' if bytes.get(i + 1) == Some(&b'(') => {
+                let mut depth = 0;
+
+                bytes[i + 1..]
+                    .iter()
+                    .position(|&b| {
+                        match b {
+                            b'(' => depth += 1,
+                            b')' => depth -= 1,
+                            _ => (),
+                        }
+
+                        depth == 0
+                    })
+                    .map(|p| i + 1 + p + 1)
+            },
+            b'[' => shortcut_reference_end(s, i, labels),
+            // the label of a full reference link
+            b']' if bytes.get(i + 1) == Some(&b'[') => {
+                bytes[i + 1..].iter().position(|&b| b == b']').map(|p| i + 1 + p + 1)
+            },
+            b'<' => html_skipped_end(s, i),
+            b'&' => character_reference_len(bytes, i).map(|len| i + len),
+            b'h' if (s[i..].starts_with("http://") || s[i..].starts_with("https://"))
+                && (i == 0 || !bytes[i - 1].is_ascii_alphanumeric()) =>
+            {
+                Some(
+                    bytes[i..]
+                        .iter()
+                        .position(|b| b.is_ascii_whitespace() || *b == b'<' || *b == b')')
+                        .map_or(bytes.len(), |p| i + p),
+                )
+            },
+            _ => None,
+        };
+
+        match markup_end {
+            Some(end) => {
+                push_segment(segments, i..end, true);
+
+                i = end;
+            },
+            None => {
+                let len = s[i..].chars().next().unwrap().len_utf8();
+
+                push_segment(segments, i..i + len, false);
+
+                i += len;
+            },
+        }
+    }
+}
+
+fn markdown_segments(s: &str) -> Vec<(Range<usize>, bool)> {
+    let mut segments = Vec::new();
+
+    let labels: HashSet<String> =
+        s.lines().filter_map(link_reference_definition).map(normalize_link_label).collect();
+
+    let mut fence: Option<(u8, usize)> = None;
+    let mut previous_blank = true;
+    let mut in_indented_code = false;
+
+    let mut offset = 0;
+
+    for raw_line in s.split_inclusive('\n') {
+        let range = offset..offset + raw_line.len();
+
+        offset += raw_line.len();
+
+        let line = raw_line.trim_end_matches(['\n', '\r']);
+
+        if let Some((c, len)) = fence {
+            push_segment(&mut segments, range, true);
+
+            if code_fence(line).map_or(false, |(close_c, close_len)| {
+                close_c == c && close_len >= len && line.trim().bytes().all(|b| b == c)
+            }) {
+                fence = None;
+            }
+
+            continue;
+        }
+
+        if line.trim().is_empty() {
+            push_segment(&mut segments, range, in_indented_code);
+
+            previous_blank = true;
+
+            continue;
+        }
+
+        if let Some(f) = code_fence(line) {
+            push_segment(&mut segments, range, true);
+
+            fence = Some(f);
+            previous_blank = false;
+            in_indented_code = false;
+
+            continue;
+        }
+
+        in_indented_code = (line.starts_with("    ") || line.starts_with('\t'))
+            && (previous_blank || in_indented_code);
+        previous_blank = false;
+
+        if in_indented_code || link_reference_definition(line).is_some() {
+            push_segment(&mut segments, range, true);
+        } else {
+            markdown_inline_segments(s, range, &labels, &mut segments);
+        }
+    }
+
+    segments
+}
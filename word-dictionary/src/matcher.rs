@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use aho_corasick::{AhoCorasick, MatchKind};
+
+use crate::Dictionary;
+
+/// What kind of word a pattern of a `Matcher` is.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum MatchTarget {
+    Left,
+    /// A right word with its position in the history of the entry. The last one is the current right word.
+    Right {
+        history_index: usize,
+    },
+}
+
+/// A word found by a `Matcher`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TextMatch {
+    /// The byte range of the matched text.
+    pub range:  Range<usize>,
+    /// The index of the entry.
+    pub index:  usize,
+    pub target: MatchTarget,
+}
+
+/// A compiled Aho-Corasick automaton over the words of a dictionary, which finds all of them in a text in a single pass. The case of ASCII letters is ignored.
+#[derive(Debug, Clone)]
+pub struct Matcher {
+    automaton: AhoCorasick,
+    /// The entry index and the target of each pattern.
+    patterns:  Vec<(usize, MatchTarget)>,
+}
+
+impl Matcher {
+    /// Build a matcher over the left words of a dictionary, and optionally all right words in their histories.
+    pub fn new(dictionary: &Dictionary, include_right: bool) -> Matcher {
+        let mut words: Vec<&str> = Vec::with_capacity(dictionary.count());
+        let mut patterns = Vec::with_capacity(dictionary.count());
+
+        for (index, left) in dictionary.left.iter().enumerate() {
+            words.push(left);
+            patterns.push((index, MatchTarget::Left));
+
+            if include_right {
+                for (history_index, right) in dictionary.right[index].iter().enumerate() {
+                    words.push(right);
+                    patterns.push((index, MatchTarget::Right {
+                        history_index,
+                    }));
+                }
+            }
+        }
+
+        let automaton = AhoCorasick::builder()
+            .ascii_case_insensitive(true)
+            .match_kind(MatchKind::Standard)
+            .build(words)
+            .unwrap();
+
+        Matcher {
+            automaton,
+            patterns,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn empty() -> Matcher {
+        Matcher {
+            automaton: AhoCorasick::new(Vec::<&str>::new()).unwrap(), patterns: Vec::new()
+        }
+    }
+
+    /// Get the count of words in this matcher.
+    #[inline]
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Find all words in a text, including overlapping ones. Matches are sorted by their start positions, and longer matches come first at the same position.
+    pub fn find_all<S: AsRef<str>>(&self, s: S) -> Vec<TextMatch> {
+        let mut matches: Vec<TextMatch> = self
+            .automaton
+            .find_overlapping_iter(s.as_ref())
+            .map(|m| {
+                let (index, target) = self.patterns[m.pattern().as_usize()];
+
+                TextMatch {
+                    range: m.range(),
+                    index,
+                    target,
+                }
+            })
+            .collect();
+
+        matches
+            .sort_by(|a, b| a.range.start.cmp(&b.range.start).then(b.range.end.cmp(&a.range.end)));
+
+        matches
+    }
+}
+
+impl Dictionary {
+    /// Get the matcher over the left words and the right words of this dictionary. It is rebuilt after this dictionary is read or changed.
+    #[inline]
+    pub fn matcher(&self) -> &Matcher {
+        &self.matcher
+    }
+
+    #[inline]
+    pub(crate) fn rebuild_matcher(&mut self) {
+        self.matcher = Matcher::new(self, true);
+    }
+}
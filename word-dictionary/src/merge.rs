@@ -0,0 +1,141 @@
+use std::fmt::{self, Display, Formatter};
+
+use crate::{Dictionary, OperationKind, WriteError};
+
+/// How to resolve an entry whose current right word is different in the other dictionary.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum MergeStrategy {
+    /// Keep our current right word. Their right words become older revisions.
+    KeepOurs,
+    /// Take their current right word. Our right words become older revisions.
+    KeepTheirs,
+    /// Keep our history, and append their current right word as the newest revision.
+    AppendTheirs,
+    /// Leave the entry untouched and report the conflict, so that it can be resolved later, for example by `add_edit`.
+    #[default]
+    Collect,
+}
+
+/// An entry whose current right word is different in the two dictionaries.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct MergeConflict {
+    /// The index of our entry before merging.
+    pub index:               usize,
+    pub left_string:         String,
+    /// Our right words, from the oldest one to the current one.
+    pub right_strings:       Vec<String>,
+    /// Their right words, from the oldest one to the current one.
+    pub other_right_strings: Vec<String>,
+}
+
+impl Display for MergeConflict {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        f.write_fmt(format_args!(
+            "conflict at {}, the left string {:?} is mapped to {:?} rather than {:?}",
+            self.index,
+            self.left_string,
+            self.other_right_strings.last().unwrap(),
+            self.right_strings.last().unwrap()
+        ))
+    }
+}
+
+/// The result of a merge.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct MergeReport {
+    /// The count of entries which only exist in the other dictionary.
+    pub added:     usize,
+    /// The count of existing entries whose histories are changed.
+    pub updated:   usize,
+    /// Entries whose current right words are different. With `MergeStrategy::Collect`, they are not applied.
+    pub conflicts: Vec<MergeConflict>,
+}
+
+/// Insert the right words of `other` which are not in `base` before the current right word of `base`, in order.
+pub(crate) fn combine_histories(base: &[String], other: &[String]) -> Vec<String> {
+    let (current, history) = base.split_last().unwrap();
+
+    let mut combined = history.to_vec();
+
+    for s in other {
+        if s != current && !combined.contains(s) {
+            combined.push(s.clone());
+        }
+    }
+
+    combined.push(current.clone());
+
+    combined
+}
+
+impl Dictionary {
+    /// Merge the entries of another dictionary into this dictionary. Left words are compared ignoring the case of ASCII letters. Entries which only exist in the other dictionary are added with their histories, and histories of entries with the same current right word are combined, preserving their order. The dictionary file is written once after all entries are applied.
+    pub fn merge(
+        &mut self,
+        other: &Dictionary,
+        strategy: MergeStrategy,
+    ) -> Result<MergeReport, WriteError> {
+        let mut report = MergeReport::default();
+
+        let snapshot = self.snapshot();
+
+        for (left, other_right) in other.iter() {
+            let index = match self.find_left_strictly(left, 0) {
+                Some(index) => index,
+                None => {
+                    self.left.push(String::from(left));
+                    self.right.push(other_right.to_vec());
+
+                    report.added += 1;
+
+                    continue;
+                },
+            };
+
+            let right = &self.right[index];
+
+            let combined = if right.last() == other_right.last() {
+                combine_histories(right, other_right)
+            } else {
+                report.conflicts.push(MergeConflict {
+                    index,
+                    left_string: self.left[index].clone(),
+                    right_strings: right.clone(),
+                    other_right_strings: other_right.to_vec(),
+                });
+
+                match strategy {
+                    MergeStrategy::KeepOurs => combine_histories(right, other_right),
+                    MergeStrategy::KeepTheirs => combine_histories(other_right, right),
+                    MergeStrategy::AppendTheirs => {
+                        let (other_current, other_history) = other_right.split_last().unwrap();
+
+                        let mut combined = combine_histories(right, other_history);
+
+                        combined.push(other_current.clone());
+
+                        combined
+                    },
+                    MergeStrategy::Collect => continue,
+                }
+            };
+
+            if &combined != right {
+                self.right[index] = combined;
+
+                report.updated += 1;
+            }
+        }
+
+        if report.added > 0 || report.updated > 0 {
+            self.rebuild_prefix_index();
+
+            self.record(OperationKind::Merge, snapshot);
+
+            self.write_data()?;
+        }
+
+        Ok(report)
+    }
+}
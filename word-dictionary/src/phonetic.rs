@@ -0,0 +1,500 @@
+use crate::{edit_distance, Dictionary, Match, MatchKind, MatchTarget};
+
+/// The Double Metaphone keys of a word. Spelling variants of a name, like `Alduin` and `Aldwin`, usually share a key.
+#[derive(Debug, Clone, Eq, PartialEq, Default)]
+pub struct PhoneticKey {
+    pub primary:   String,
+    /// The key of an alternative pronunciation. It equals the primary key if there is none.
+    pub alternate: String,
+}
+
+impl PhoneticKey {
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.primary.is_empty()
+    }
+
+    /// Whether two keys share a pronunciation.
+    #[inline]
+    pub fn sounds_like(&self, other: &PhoneticKey) -> bool {
+        !self.is_empty()
+            && (self.primary == other.primary
+                || self.primary == other.alternate
+                || self.alternate == other.primary
+                || self.alternate == other.alternate)
+    }
+}
+
+/// Letters of a word being encoded, with the two keys being built.
+struct Encoder {
+    letters:   Vec<char>,
+    primary:   String,
+    alternate: String,
+}
+
+impl Encoder {
+    #[inline]
+    fn at(&self, i: usize) -> char {
+        self.letters.get(i).copied().unwrap_or('\0')
+    }
+
+    #[inline]
+    fn is_vowel(&self, i: usize) -> bool {
+        matches!(self.at(i), 'A' | 'E' | 'I' | 'O' | 'U' | 'Y')
+    }
+
+    /// Whether one of the strings is at a position.
+    #[inline]
+    fn is_at(&self, i: usize, strings: &[&str]) -> bool {
+        strings.iter().any(|s| s.chars().enumerate().all(|(j, c)| self.at(i + j) == c))
+    }
+
+    #[inline]
+    fn push(&mut self, primary: &str, alternate: &str) {
+        self.primary.push_str(primary);
+        self.alternate.push_str(alternate);
+    }
+
+    /// Encode the letter at a position, and get the position of the next one.
+    fn encode(&mut self, i: usize) -> usize {
+        match self.at(i) {
+            'A' | 'E' | 'I' | 'O' | 'U' | 'Y' => {
+                // only the first vowel is written
+                if i == 0 {
+                    self.push("A", "A");
+                }
+
+                i + 1
+            },
+            'B' => {
+                self.push("P", "P");
+
+                if self.at(i + 1) == 'B' {
+                    i + 2
+                } else {
+                    i + 1
+                }
+            },
+            'C' => self.encode_c(i),
+            'D' => {
+                if self.is_at(i, &["DGE", "DGI", "DGY"]) {
+                    self.push("J", "J");
+
+                    i + 3
+                } else if self.is_at(i, &["DG"]) {
+                    self.push("TK", "TK");
+
+                    i + 2
+                } else {
+                    self.push("T", "T");
+
+                    if matches!(self.at(i + 1), 'D' | 'T') {
+                        i + 2
+                    } else {
+                        i + 1
+                    }
+                }
+            },
+            'G' => self.encode_g(i),
+            'H' => {
+                // only voiced between a vowel or the start and a vowel
+                if (i == 0 || self.is_vowel(i - 1)) && self.is_vowel(i + 1) {
+                    self.push("H", "H");
+                }
+
+                i + 1
+            },
+            'J' => {
+                if self.is_at(i, &["JOSE"]) {
+                    self.push("H", "H");
+                } else if i == 0 {
+                    self.push("J", "A");
+                } else {
+                    self.push("J", "H");
+                }
+
+                if self.at(i + 1) == 'J' {
+                    i + 2
+                } else {
+                    i + 1
+                }
+            },
+            'P' => {
+                if self.at(i + 1) == 'H' {
+                    self.push("F", "F");
+
+                    i + 2
+                } else {
+                    self.push("P", "P");
+
+                    if matches!(self.at(i + 1), 'P' | 'B') {
+                        i + 2
+                    } else {
+                        i + 1
+                    }
+                }
+            },
+            'Q' => {
+                self.push("K", "K");
+
+                if self.at(i + 1) == 'Q' {
+                    i + 2
+                } else {
+                    i + 1
+                }
+            },
+            'S' => self.encode_s(i),
+            'T' => {
+                if self.is_at(i, &["TION", "TIA", "TCH"]) {
+                    self.push("X", "X");
+
+                    i + 3
+                } else if self.is_at(i, &["TH"]) {
+                    self.push("0", "T");
+
+                    i + 2
+                } else {
+                    self.push("T", "T");
+
+                    if matches!(self.at(i + 1), 'T' | 'D') {
+                        i + 2
+                    } else {
+                        i + 1
+                    }
+                }
+            },
+            'V' => {
+                self.push("F", "F");
+
+                if self.at(i + 1) == 'V' {
+                    i + 2
+                } else {
+                    i + 1
+                }
+            },
+            'W' => {
+                if self.at(i + 1) == 'R' {
+                    self.push("R", "R");
+
+                    i + 2
+                } else {
+                    if i == 0 && (self.is_vowel(i + 1) || self.at(i + 1) == 'H') {
+                        self.push("A", "F");
+                    }
+
+                    // silent after a vowel or within a consonant cluster
+                    i + 1
+                }
+            },
+            'X' => {
+                // silent at the end of French words
+                let french = i + 1 == self.letters.len()
+                    && i >= 2
+                    && (self.is_at(i - 2, &["AU", "OU"])
+                        || (i >= 3 && self.is_at(i - 3, &["EAU", "IAU"])));
+
+                if i == 0 {
+                    self.push("S", "S");
+                } else if !french {
+                    self.push("KS", "KS");
+                }
+
+                if matches!(self.at(i + 1), 'C' | 'X') {
+                    i + 2
+                } else {
+                    i + 1
+                }
+            },
+            'Z' => {
+                if self.at(i + 1) == 'H' {
+                    self.push("J", "J");
+
+                    i + 2
+                } else {
+                    self.push("S", "S");
+
+                    if self.at(i + 1) == 'Z' {
+                        i + 2
+                    } else {
+                        i + 1
+                    }
+                }
+            },
+            c => {
+                // F, K, L, M, N and R are written as they are
+                let mut s = [0; 4];
+
+                let s = c.encode_utf8(&mut s);
+
+                self.push(s, s);
+
+                if self.at(i + 1) == c {
+                    i + 2
+                } else {
+                    i + 1
+                }
+            },
+        }
+    }
+
+    fn encode_c(&mut self, i: usize) -> usize {
+        if self.is_at(i, &["CIA"]) {
+            self.push("X", "X");
+
+            i + 3
+        } else if self.is_at(i, &["CH"]) {
+            if i == 0 && self.is_at(i + 2, &["R", "L", "A", "O", "U"]) {
+                // Christ, Chaos
+                self.push("K", "K");
+            } else {
+                self.push("X", "K");
+            }
+
+            i + 2
+        } else if self.is_at(i, &["CZ"]) {
+            self.push("S", "X");
+
+            i + 2
+        } else if self.is_at(i, &["CCE", "CCI", "CCY"]) {
+            self.push("KS", "KS");
+
+            i + 3
+        } else if self.is_at(i, &["CK", "CG", "CQ", "CC"]) {
+            self.push("K", "K");
+
+            i + 2
+        } else if self.is_at(i, &["CIO", "CIE"]) {
+            self.push("S", "X");
+
+            i + 2
+        } else if self.is_at(i, &["CI", "CE", "CY"]) {
+            self.push("S", "S");
+
+            i + 2
+        } else {
+            self.push("K", "K");
+
+            if matches!(self.at(i + 1), 'K' | 'Q') {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+
+    fn encode_g(&mut self, i: usize) -> usize {
+        match self.at(i + 1) {
+            'H' => {
+                if i == 0 {
+                    if self.at(i + 2) == 'I' {
+                        self.push("J", "J");
+                    } else {
+                        self.push("K", "K");
+                    }
+                } else if !self.is_vowel(i - 1) {
+                    self.push("K", "K");
+                } else if i + 2 == self.letters.len() && i >= 2 && self.is_at(i - 2, &["AU", "OU"])
+                {
+                    // laugh, tough
+                    self.push("F", "F");
+                }
+
+                i + 2
+            },
+            'N' => {
+                if i == 1 && self.is_vowel(0) {
+                    self.push("KN", "N");
+                } else {
+                    self.push("N", "KN");
+                }
+
+                i + 2
+            },
+            'E' | 'I' | 'Y' => {
+                if i == 0 {
+                    self.push("K", "J");
+                } else {
+                    self.push("J", "K");
+                }
+
+                i + 1
+            },
+            'G' => {
+                self.push("K", "K");
+
+                i + 2
+            },
+            _ => {
+                self.push("K", "K");
+
+                i + 1
+            },
+        }
+    }
+
+    fn encode_s(&mut self, i: usize) -> usize {
+        if self.is_at(i, &["SH"]) {
+            self.push("X", "X");
+
+            i + 2
+        } else if self.is_at(i, &["SIO", "SIA"]) {
+            self.push("S", "X");
+
+            i + 3
+        } else if self.is_at(i, &["SCH"]) {
+            self.push("SK", "X");
+
+            i + 3
+        } else if self.is_at(i, &["SCE", "SCI", "SCY"]) {
+            self.push("S", "S");
+
+            i + 3
+        } else if self.is_at(i, &["SC"]) {
+            self.push("SK", "SK");
+
+            i + 2
+        } else if self.is_at(i, &["SZ"]) {
+            self.push("S", "X");
+
+            i + 2
+        } else {
+            self.push("S", "S");
+
+            if self.at(i + 1) == 'S' {
+                i + 2
+            } else {
+                i + 1
+            }
+        }
+    }
+}
+
+/// Get the Double Metaphone keys of a word. Characters which are not Latin letters are ignored.
+pub fn double_metaphone<S: AsRef<str>>(word: S) -> PhoneticKey {
+    let letters: Vec<char> = word
+        .as_ref()
+        .chars()
+        .filter_map(|c| match c {
+            'ç' | 'Ç' => Some('S'),
+            'ñ' | 'Ñ' => Some('N'),
+            _ if c.is_ascii_alphabetic() => Some(c.to_ascii_uppercase()),
+            _ => None,
+        })
+        .collect();
+
+    let mut encoder = Encoder {
+        letters,
+        primary: String::new(),
+        alternate: String::new(),
+    };
+
+    // silent initial letters
+    let mut i = if encoder.is_at(0, &["GN", "KN", "PN", "WR", "PS"]) { 1 } else { 0 };
+
+    while i < encoder.letters.len() {
+        i = encoder.encode(i);
+    }
+
+    PhoneticKey {
+        primary: encoder.primary, alternate: encoder.alternate
+    }
+}
+
+/// The sound of a word, which is compared with sounds of the same kind.
+enum Sound {
+    Latin(PhoneticKey),
+    #[cfg(feature = "pinyin")]
+    Pinyin(String),
+}
+
+impl Dictionary {
+    fn sound(&self, word: &str) -> Option<Sound> {
+        #[cfg(feature = "pinyin")]
+        if let Some(key) = self.pinyin_key(word) {
+            return Some(Sound::Pinyin(key));
+        }
+
+        let key = double_metaphone(word);
+
+        if key.is_empty() {
+            None
+        } else {
+            Some(Sound::Latin(key))
+        }
+    }
+
+    /// Whether two words sound alike, and how closely.
+    fn compare_sounds(&self, a: &Sound, b: &Sound) -> Option<MatchKind> {
+        match (a, b) {
+            (Sound::Latin(a), Sound::Latin(b)) => {
+                if a.sounds_like(b) {
+                    Some(MatchKind::Exact)
+                } else if is_close(&a.primary, &b.primary) {
+                    Some(MatchKind::Fuzzy)
+                } else {
+                    None
+                }
+            },
+            #[cfg(feature = "pinyin")]
+            (Sound::Pinyin(a), Sound::Pinyin(b)) => {
+                if a == b {
+                    Some(MatchKind::Exact)
+                } else if is_close(a, b) {
+                    Some(MatchKind::Fuzzy)
+                } else {
+                    None
+                }
+            },
+            #[cfg(feature = "pinyin")]
+            _ => None,
+        }
+    }
+
+    /// Find entries whose left words or right words, including the history, sound like a word. Every found entry is yielded once, in order.
+    ///
+    /// Left words and Latin right words are compared by their Double Metaphone keys. With the `pinyin` feature, Chinese right words are compared by their toneless pinyin, and commonly confused sounds like `zh` and `z` are treated as the same. Exact matches share a key, and fuzzy matches have keys within a small edit distance. Left words are preferred, and then newer right words.
+    pub fn find_similar_sounding<S: AsRef<str>>(&self, s: S) -> impl Iterator<Item = Match> + '_ {
+        let sound = self.sound(s.as_ref());
+
+        self.iter().enumerate().filter_map(move |(index, (left, right))| {
+            let sound = sound.as_ref()?;
+
+            let mut best: Option<(MatchKind, MatchTarget)> = None;
+
+            let words = std::iter::once((left, MatchTarget::Left)).chain(
+                right.iter().enumerate().rev().map(|(history_index, s)| {
+                    (s.as_str(), MatchTarget::Right {
+                        history_index,
+                    })
+                }),
+            );
+
+            for (word, target) in words {
+                let kind = match self.sound(word) {
+                    Some(other) => self.compare_sounds(sound, &other),
+                    None => None,
+                };
+
+                if let Some(kind) = kind {
+                    if best.map_or(true, |(best_kind, _)| kind < best_kind) {
+                        best = Some((kind, target));
+                    }
+                }
+            }
+
+            best.map(|(kind, target)| Match {
+                index,
+                kind,
+                target,
+            })
+        })
+    }
+}
+
+/// Whether two keys are within a third of the shorter one in edit distance.
+#[inline]
+fn is_close(a: &str, b: &str) -> bool {
+    let max_distance = a.chars().count().min(b.chars().count()) / 3;
+
+    max_distance > 0 && edit_distance(a, b) <= max_distance
+}
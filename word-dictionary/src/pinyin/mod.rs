@@ -0,0 +1,179 @@
+use std::collections::HashMap;
+
+use once_cell::sync::Lazy;
+
+use crate::{Dictionary, Match, MatchKind, MatchTarget};
+
+/// Toneless pinyin of CJK ideographs. Each line is a syllable followed by its characters.
+static TABLE: &str = include_str!("table.txt");
+
+/// The table is parsed on the first pinyin lookup, and shared by all dictionaries.
+static PINYIN: Lazy<PinyinTable> = Lazy::new(PinyinTable::default);
+
+/// A character-to-pinyin table.
+struct PinyinTable {
+    map: HashMap<char, &'static str>,
+}
+
+impl Default for PinyinTable {
+    fn default() -> Self {
+        let mut map = HashMap::new();
+
+        for line in TABLE.lines().filter(|line| !line.starts_with('#')) {
+            if let Some((syllable, characters)) = line.split_once(' ') {
+                for c in characters.chars() {
+                    map.insert(c, syllable);
+                }
+            }
+        }
+
+        PinyinTable {
+            map,
+        }
+    }
+}
+
+impl PinyinTable {
+    /// Split a word into syllables. Letters and digits which are not ideographs are kept as single-character syllables, and other characters are dropped.
+    fn syllables(&self, s: &str) -> Vec<String> {
+        s.chars()
+            .filter_map(|c| match self.map.get(&c) {
+                Some(syllable) => Some(String::from(*syllable)),
+                None if c.is_alphanumeric() => Some(c.to_lowercase().collect()),
+                None => None,
+            })
+            .collect()
+    }
+}
+
+/// Normalize a pinyin query. Spaces and apostrophes are removed, and `ü` is written as `v`. Return `None` if it is not a pinyin query.
+fn normalize_query(query: &str) -> Option<String> {
+    let mut normalized = String::with_capacity(query.len());
+
+    for c in query.chars() {
+        match c {
+            'a'..='z' | '0'..='9' => normalized.push(c),
+            'A'..='Z' => normalized.push(c.to_ascii_lowercase()),
+            'ü' | 'Ü' => normalized.push('v'),
+            ' ' | '\'' | '\u{2019}' => (),
+            _ => return None,
+        }
+    }
+
+    if normalized.is_empty() {
+        None
+    } else {
+        Some(normalized)
+    }
+}
+
+/// Whether the query matches syllables from the start. Each syllable is written in full or by its initial, and the last one can be incomplete.
+fn match_syllables(syllables: &[String], query: &str) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+
+    let syllable = match syllables.first() {
+        Some(syllable) => syllable,
+        None => return false,
+    };
+
+    if syllable.starts_with(query) {
+        return true;
+    }
+
+    // characters which are not in the table can be multibyte
+    let mut lengths = vec![syllable.len(), syllable.chars().next().unwrap().len_utf8()];
+
+    if ["zh", "ch", "sh"].iter().any(|initial| syllable.starts_with(initial)) {
+        lengths.push(2);
+    }
+
+    lengths.into_iter().any(|len| {
+        query.starts_with(&syllable[..len]) && match_syllables(&syllables[1..], &query[len..])
+    })
+}
+
+/// Match a word with a normalized query. Return whether the whole word is matched in full spelling.
+fn match_pinyin(table: &PinyinTable, word: &str, query: &str) -> Option<MatchKind> {
+    let syllables = table.syllables(word);
+
+    if syllables.concat() == query {
+        return Some(MatchKind::Exact);
+    }
+
+    (0..syllables.len())
+        .any(|i| match_syllables(&syllables[i..], query))
+        .then_some(MatchKind::Substring)
+}
+
+/// Merge sounds which are commonly confused, like `zh` and `z`, or `ing` and `in`.
+fn fuzzy_syllable(syllable: &str) -> String {
+    let mut syllable = match syllable.get(..2) {
+        Some("zh") | Some("ch") | Some("sh") => format!("{}{}", &syllable[..1], &syllable[2..]),
+        _ => String::from(syllable),
+    };
+
+    if syllable.ends_with("ang") || syllable.ends_with("eng") || syllable.ends_with("ing") {
+        syllable.pop();
+    }
+
+    syllable
+}
+
+impl Dictionary {
+    /// Get the toneless pinyin of a word with commonly confused sounds merged, like `zh` and `z`. Return `None` if the word has no Chinese characters.
+    pub fn pinyin_key<S: AsRef<str>>(&self, s: S) -> Option<String> {
+        let s = s.as_ref();
+
+        if !s.chars().any(|c| PINYIN.map.contains_key(&c)) {
+            return None;
+        }
+
+        Some(PINYIN.syllables(s).iter().map(|syllable| fuzzy_syllable(syllable)).collect())
+    }
+
+    /// Find a right word by toneless pinyin, which can be written in full like `a er du yin` or `aerduyin`, or by initials like `aedy`.
+    #[inline]
+    pub fn find_right_pinyin<S: AsRef<str>>(&self, s: S, mut start_index: usize) -> Option<usize> {
+        let size = self.count();
+
+        if size == 0 {
+            return None;
+        }
+
+        start_index %= size;
+
+        let query = normalize_query(s.as_ref())?;
+
+        (0..size).map(|i| (start_index + i) % size).find(|&index| {
+            self.right[index].iter().any(|s| match_pinyin(&PINYIN, s, &query).is_some())
+        })
+    }
+
+    /// Search right words, including the history, by toneless pinyin. Every matched entry is yielded once, in order.
+    pub fn search_right_pinyin<S: AsRef<str>>(&self, query: S) -> impl Iterator<Item = Match> + '_ {
+        let query = normalize_query(query.as_ref());
+
+        self.right.iter().enumerate().filter_map(move |(index, right)| {
+            let query = query.as_deref()?;
+
+            let (history_index, kind) = right
+                .iter()
+                .enumerate()
+                .rev()
+                .filter_map(|(history_index, s)| {
+                    match_pinyin(&PINYIN, s, query).map(|kind| (history_index, kind))
+                })
+                .min_by_key(|(_, kind)| *kind)?;
+
+            Some(Match {
+                index,
+                kind,
+                target: MatchTarget::Right {
+                    history_index,
+                },
+            })
+        })
+    }
+}
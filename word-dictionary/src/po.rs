@@ -0,0 +1,437 @@
+use std::io::{self, Read, Write};
+
+use crate::{Dictionary, FormatError, TermIssue, TransformOptions};
+
+/// A message of a gettext PO file.
+#[derive(Debug, Clone, Default)]
+pub struct PoEntry {
+    /// The line of the `msgid` keyword, or the first line of the entry if it has no `msgid`.
+    pub line:         usize,
+    /// Raw comment lines, including obsolete (`#~`) lines.
+    pub comments:     Vec<String>,
+    pub msgctxt:      Option<String>,
+    pub msgid:        String,
+    pub msgid_plural: Option<String>,
+    /// `msgstr`, or `msgstr[0]`, `msgstr[1]`, ... for a plural message.
+    pub msgstr:       Vec<String>,
+    raw:              Option<RawEntry>,
+}
+
+/// The text of an entry as it was read, so it can be written back as it was if the entry is not changed.
+#[derive(Debug, Clone)]
+struct RawEntry {
+    /// The blank lines before the entry.
+    leading: String,
+    text:    String,
+    /// The entry as it was parsed.
+    parsed:  Box<PoEntry>,
+}
+
+impl PartialEq for PoEntry {
+    #[inline]
+    fn eq(&self, other: &PoEntry) -> bool {
+        self.line == other.line
+            && self.comments == other.comments
+            && self.msgctxt == other.msgctxt
+            && self.msgid == other.msgid
+            && self.msgid_plural == other.msgid_plural
+            && self.msgstr == other.msgstr
+    }
+}
+
+impl Eq for PoEntry {}
+
+impl PoEntry {
+    /// Whether this entry is the header, whose `msgid` is empty.
+    #[inline]
+    pub fn is_header(&self) -> bool {
+        self.msgid.is_empty()
+    }
+
+    #[inline]
+    pub fn is_fuzzy(&self) -> bool {
+        self.comments
+            .iter()
+            .filter_map(|c| c.strip_prefix("#,"))
+            .any(|flags| flags.split(',').any(|flag| flag.trim() == "fuzzy"))
+    }
+
+    /// Add the `fuzzy` flag.
+    pub fn set_fuzzy(&mut self) {
+        if self.is_fuzzy() {
+            return;
+        }
+
+        match self.comments.iter_mut().find(|c| c.starts_with("#,")) {
+            Some(flags) => flags.push_str(", fuzzy"),
+            None => {
+                // flags go before the previous-message (`#|`) comments
+                let position = self
+                    .comments
+                    .iter()
+                    .position(|c| c.starts_with("#|") || c.starts_with("#~"))
+                    .unwrap_or(self.comments.len());
+
+                self.comments.insert(position, String::from("#, fuzzy"));
+            },
+        }
+    }
+
+    /// The source text of a `msgstr`, which is `msgid_plural` for plural forms.
+    #[inline]
+    fn source(&self, msgstr_index: usize) -> &str {
+        match &self.msgid_plural {
+            Some(msgid_plural) if msgstr_index > 0 => msgid_plural.as_str(),
+            _ => self.msgid.as_str(),
+        }
+    }
+
+    #[inline]
+    fn has_message(&self) -> bool {
+        !self.msgid.is_empty() || !self.msgstr.is_empty()
+    }
+
+    /// Keep the text of this entry, which is read after its leading blank lines.
+    fn set_raw(&mut self, mut leading: String) {
+        let leading_len = leading
+            .split_inclusive('\n')
+            .take_while(|line| line.trim().is_empty())
+            .map(|line| line.len())
+            .sum();
+
+        let text = leading.split_off(leading_len);
+
+        self.raw = Some(RawEntry {
+            leading,
+            text,
+            parsed: Box::new(self.clone()),
+        });
+    }
+}
+
+/// A gettext PO file.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct PoFile {
+    pub entries: Vec<PoEntry>,
+}
+
+/// An issue found in a PO file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct PoIssue {
+    /// The line of the `msgid` keyword.
+    pub line:         usize,
+    pub msgid:        String,
+    /// The index of the checked `msgstr`.
+    pub msgstr_index: usize,
+    pub issue:        TermIssue,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+enum Field {
+    Msgctxt,
+    Msgid,
+    MsgidPlural,
+    Msgstr(usize),
+}
+
+fn unescape(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    let mut chars = s.chars();
+
+    while let Some(c) = chars.next() {
+        if c == '\\' {
+            match chars.next() {
+                Some('n') => output.push('\n'),
+                Some('t') => output.push('\t'),
+                Some('r') => output.push('\r'),
+                Some(c) => output.push(c),
+                None => output.push('\\'),
+            }
+        } else {
+            output.push(c);
+        }
+    }
+
+    output
+}
+
+fn escape(s: &str) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '\n' => output.push_str("\\n"),
+            '\t' => output.push_str("\\t"),
+            '\r' => output.push_str("\\r"),
+            '"' => output.push_str("\\\""),
+            '\\' => output.push_str("\\\\"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+fn write_field<W: Write>(writer: &mut W, keyword: &str, value: &str) -> Result<(), io::Error> {
+    let pieces: Vec<&str> = value.split_inclusive('\n').collect();
+
+    if pieces.len() > 1 {
+        writeln!(writer, "{} \"\"", keyword)?;
+
+        for piece in pieces {
+            writeln!(writer, "\"{}\"", escape(piece))?;
+        }
+
+        Ok(())
+    } else {
+        writeln!(writer, "{} \"{}\"", keyword, escape(value))
+    }
+}
+
+/// Finish an entry with the lines read for it.
+#[inline]
+fn push_entry(entries: &mut Vec<PoEntry>, entry: &mut PoEntry, raw: &mut String) {
+    let mut entry = std::mem::take(entry);
+
+    entry.set_raw(std::mem::take(raw));
+
+    entries.push(entry);
+}
+
+impl PoFile {
+    /// Parse a PO file.
+    pub fn parse<R: Read>(mut reader: R) -> Result<PoFile, FormatError> {
+        let mut s = String::new();
+
+        reader.read_to_string(&mut s)?;
+
+        let mut entries = Vec::new();
+
+        let mut entry = PoEntry::default();
+        let mut field: Option<Field> = None;
+
+        // the lines which are read since the last entry
+        let mut raw = String::new();
+
+        for (i, raw_line) in s.split_inclusive('\n').enumerate() {
+            let line_number = i + 1;
+
+            let line = raw_line.trim();
+            let line = line.strip_prefix('\u{FEFF}').unwrap_or(line);
+
+            let malformed = |reason: &str| FormatError::Malformed {
+                line:   line_number,
+                reason: String::from(reason),
+            };
+
+            if line.is_empty() {
+                if entry.has_message() || !entry.comments.is_empty() {
+                    push_entry(&mut entries, &mut entry, &mut raw);
+                }
+
+                raw.push_str(raw_line);
+                field = None;
+
+                continue;
+            }
+
+            if line.starts_with('#') {
+                if entry.has_message() {
+                    push_entry(&mut entries, &mut entry, &mut raw);
+                    field = None;
+                }
+
+                raw.push_str(raw_line);
+
+                if entry.comments.is_empty() {
+                    entry.line = line_number;
+                }
+
+                entry.comments.push(String::from(line));
+
+                continue;
+            }
+
+            let (keyword, value) = match line.find('"') {
+                Some(i) => (line[..i].trim(), &line[i..]),
+                None => return Err(malformed("expected a quoted string")),
+            };
+
+            if value.len() < 2 || !value.ends_with('"') {
+                return Err(malformed("a string is not closed"));
+            }
+
+            let value = unescape(&value[1..value.len() - 1]);
+
+            let new_field = match keyword {
+                "" => match field {
+                    Some(field) => field,
+                    None => return Err(malformed("a string does not follow any keyword")),
+                },
+                "msgctxt" => Field::Msgctxt,
+                "msgid" => Field::Msgid,
+                "msgid_plural" => Field::MsgidPlural,
+                "msgstr" => Field::Msgstr(0),
+                _ => match keyword.strip_prefix("msgstr[").and_then(|s| s.strip_suffix(']')) {
+                    Some(n) => Field::Msgstr(n.parse().map_err(|_| malformed("bad plural index"))?),
+                    None => return Err(malformed("unknown keyword")),
+                },
+            };
+
+            if !keyword.is_empty() {
+                // a new message starts at `msgctxt` or `msgid` after a finished message
+                if matches!(new_field, Field::Msgctxt | Field::Msgid) && !entry.msgstr.is_empty() {
+                    push_entry(&mut entries, &mut entry, &mut raw);
+                }
+
+                if new_field == Field::Msgid || entry.comments.is_empty() && !entry.has_message() {
+                    entry.line = line_number;
+                }
+            }
+
+            match new_field {
+                Field::Msgctxt => entry.msgctxt.get_or_insert_with(String::new).push_str(&value),
+                Field::Msgid => entry.msgid.push_str(&value),
+                Field::MsgidPlural => {
+                    entry.msgid_plural.get_or_insert_with(String::new).push_str(&value)
+                },
+                Field::Msgstr(n) => {
+                    if entry.msgstr.len() <= n {
+                        entry.msgstr.resize(n + 1, String::new());
+                    }
+
+                    entry.msgstr[n].push_str(&value);
+                },
+            }
+
+            raw.push_str(raw_line);
+            field = Some(new_field);
+        }
+
+        if entry.has_message() || !entry.comments.is_empty() {
+            push_entry(&mut entries, &mut entry, &mut raw);
+        } else if let Some(last) = entries.last_mut().and_then(|entry| entry.raw.as_mut()) {
+            // trailing blank lines
+            last.text.push_str(&raw);
+        }
+
+        Ok(PoFile {
+            entries,
+        })
+    }
+
+    /// Write this PO file. Entries which are not changed since they were read are written as they were. Other entries are re-serialized, and multi-line strings are split after each `\n`.
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), io::Error> {
+        for (i, entry) in self.entries.iter().enumerate() {
+            match &entry.raw {
+                Some(raw) if *raw.parsed == *entry => {
+                    writer.write_all(raw.leading.as_bytes())?;
+                    writer.write_all(raw.text.as_bytes())?;
+
+                    continue;
+                },
+                Some(raw) => writer.write_all(raw.leading.as_bytes())?,
+                None if i > 0 => writeln!(writer)?,
+                None => (),
+            }
+
+            for comment in entry.comments.iter() {
+                writeln!(writer, "{}", comment)?;
+            }
+
+            if !entry.has_message() {
+                continue;
+            }
+
+            if let Some(msgctxt) = &entry.msgctxt {
+                write_field(&mut writer, "msgctxt", msgctxt)?;
+            }
+
+            write_field(&mut writer, "msgid", &entry.msgid)?;
+
+            match &entry.msgid_plural {
+                Some(msgid_plural) => {
+                    write_field(&mut writer, "msgid_plural", msgid_plural)?;
+
+                    for (n, msgstr) in entry.msgstr.iter().enumerate() {
+                        write_field(&mut writer, &format!("msgstr[{}]", n), msgstr)?;
+                    }
+                },
+                None => {
+                    write_field(
+                        &mut writer,
+                        "msgstr",
+                        entry.msgstr.first().map(|s| s.as_str()).unwrap_or_default(),
+                    )?;
+                },
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl Dictionary {
+    /// Check the terms of every translated message in a PO file. See `check_terms`.
+    pub fn check_po(&self, po: &PoFile) -> Vec<PoIssue> {
+        let mut issues = Vec::new();
+
+        for entry in po.entries.iter().filter(|entry| !entry.is_header()) {
+            for (msgstr_index, msgstr) in entry.msgstr.iter().enumerate() {
+                if msgstr.is_empty() {
+                    continue;
+                }
+
+                for issue in self.check_terms(entry.source(msgstr_index), msgstr) {
+                    issues.push(PoIssue {
+                        line: entry.line,
+                        msgid: entry.msgid.clone(),
+                        msgstr_index,
+                        issue,
+                    });
+                }
+            }
+        }
+
+        issues
+    }
+
+    /// Fill empty or missing `msgstr` strings by transforming their sources. Only sources containing left words are used, and the filled messages are marked as fuzzy. Return the count of filled messages.
+    pub fn prefill_po(&self, po: &mut PoFile) -> usize {
+        let mut count = 0;
+
+        for entry in po.entries.iter_mut().filter(|entry| !entry.is_header()) {
+            let mut filled = false;
+
+            for msgstr_index in 0..entry.msgstr.len().max(1) {
+                if entry.msgstr.get(msgstr_index).map_or(false, |msgstr| !msgstr.is_empty()) {
+                    continue;
+                }
+
+                let source = entry.source(msgstr_index);
+
+                if self.find_left_occurrences(source, &TransformOptions::default()).is_empty() {
+                    continue;
+                }
+
+                let msgstr = self.transform(source);
+
+                match entry.msgstr.get_mut(msgstr_index) {
+                    Some(old_msgstr) => *old_msgstr = msgstr,
+                    None => entry.msgstr.push(msgstr),
+                }
+
+                filled = true;
+            }
+
+            if filled {
+                entry.set_fuzzy();
+
+                count += 1;
+            }
+        }
+
+        count
+    }
+}
@@ -0,0 +1,87 @@
+pub use regex::Regex;
+
+use crate::Dictionary;
+
+/// Which right words are searched by `find_right_regex`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum RevisionScope {
+    /// All right words in the history.
+    #[default]
+    History,
+    /// Only the current (last) right words.
+    Current,
+}
+
+impl RevisionScope {
+    /// Get the right words in this scope with their positions in the history, from the newest one.
+    #[inline]
+    fn revisions(self, right: &[String]) -> impl Iterator<Item = (usize, &String)> {
+        let skipped = match self {
+            RevisionScope::History => 0,
+            RevisionScope::Current => right.len().saturating_sub(1),
+        };
+
+        right.iter().enumerate().skip(skipped).rev()
+    }
+}
+
+impl Dictionary {
+    /// Find a left word which matches a regular expression.
+    #[inline]
+    pub fn find_left_regex(&self, regex: &Regex, mut start_index: usize) -> Option<usize> {
+        let size = self.count();
+
+        if size == 0 {
+            return None;
+        }
+
+        start_index %= size;
+
+        (0..size).map(|i| (start_index + i) % size).find(|&index| regex.is_match(&self.left[index]))
+    }
+
+    /// Find a right word which matches a regular expression.
+    #[inline]
+    pub fn find_right_regex(
+        &self,
+        regex: &Regex,
+        mut start_index: usize,
+        scope: RevisionScope,
+    ) -> Option<usize> {
+        let size = self.count();
+
+        if size == 0 {
+            return None;
+        }
+
+        start_index %= size;
+
+        (0..size)
+            .map(|i| (start_index + i) % size)
+            .find(|&index| scope.revisions(&self.right[index]).any(|(_, s)| regex.is_match(s)))
+    }
+
+    /// Iterate over the indices of the left words which match a regular expression, in order.
+    #[inline]
+    pub fn find_left_regex_iter<'a>(
+        &'a self,
+        regex: &'a Regex,
+    ) -> impl Iterator<Item = usize> + 'a {
+        self.left.iter().enumerate().filter(|(_, s)| regex.is_match(s)).map(|(index, _)| index)
+    }
+
+    /// Iterate over the entries whose right words match a regular expression, in order. Each entry is yielded once, with the position of the newest matched right word in its history.
+    #[inline]
+    pub fn find_right_regex_iter<'a>(
+        &'a self,
+        regex: &'a Regex,
+        scope: RevisionScope,
+    ) -> impl Iterator<Item = (usize, usize)> + 'a {
+        self.right.iter().enumerate().filter_map(move |(index, right)| {
+            scope
+                .revisions(right)
+                .find(|(_, s)| regex.is_match(s))
+                .map(|(history_index, _)| (index, history_index))
+        })
+    }
+}
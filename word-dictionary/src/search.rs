@@ -0,0 +1,265 @@
+use crate::{Dictionary, MatchTarget};
+
+/// How a word matches a query.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd)]
+pub enum MatchKind {
+    /// The word equals the query, ignoring the case of ASCII letters.
+    Exact,
+    /// The word starts with the query, ignoring case. Only `Dictionary::search` finds this kind.
+    Prefix,
+    /// The word contains the query, ignoring case.
+    Substring,
+    /// The word is within a small edit distance of the query. Only `Dictionary::search` and `Dictionary::find_similar_sounding` find this kind.
+    Fuzzy,
+}
+
+/// An entry found by `search_left` or `search_right`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Match {
+    /// The index of the entry.
+    pub index:  usize,
+    pub kind:   MatchKind,
+    /// The matched word. For right words, the position in the history is included.
+    pub target: MatchTarget,
+}
+
+/// A query prepared for case-insensitive matching.
+struct Query {
+    s:          String,
+    upper_case: String,
+    lower_case: String,
+}
+
+impl Query {
+    #[inline]
+    fn new(s: &str) -> Query {
+        Query {
+            s:          String::from(s),
+            upper_case: s.to_uppercase(),
+            lower_case: s.to_lowercase(),
+        }
+    }
+
+    #[inline]
+    fn is_exact(&self, word: &str) -> bool {
+        word.eq_ignore_ascii_case(&self.s)
+    }
+
+    #[inline]
+    fn is_prefix(&self, word: &str) -> bool {
+        word.to_lowercase().starts_with(&self.lower_case)
+    }
+
+    #[inline]
+    fn is_substring(&self, word: &str) -> bool {
+        word.to_uppercase().contains(&self.upper_case)
+            || word.to_lowercase().contains(&self.lower_case)
+    }
+
+    /// Match a word, and get the kind with the edit distance.
+    fn match_word(&self, word: &str, max_distance: usize) -> Option<(MatchKind, usize)> {
+        if self.is_exact(word) {
+            Some((MatchKind::Exact, 0))
+        } else if self.is_prefix(word) {
+            Some((MatchKind::Prefix, 0))
+        } else if self.is_substring(word) {
+            Some((MatchKind::Substring, 0))
+        } else {
+            // short queries allow fewer edits
+            let max_distance = max_distance.min(self.lower_case.chars().count() / 3);
+
+            if max_distance == 0 {
+                return None;
+            }
+
+            let distance = edit_distance(&word.to_lowercase(), &self.lower_case);
+
+            if distance <= max_distance {
+                Some((MatchKind::Fuzzy, distance))
+            } else {
+                None
+            }
+        }
+    }
+}
+
+/// Get the Levenshtein distance between two strings in characters.
+pub(crate) fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+
+        row[0] = i + 1;
+
+        for (j, &cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != cb);
+
+            diagonal = row[j + 1];
+
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+
+    row[b.len()]
+}
+
+/// Weights and limits of `Dictionary::search`. The score of a match is the weight of its kind multiplied by the weight of its side.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchOptions {
+    pub exact_weight:     f64,
+    pub prefix_weight:    f64,
+    pub substring_weight: f64,
+    /// The weight of a fuzzy match with one edit. It is divided by the edit distance.
+    pub fuzzy_weight:     f64,
+    pub left_weight:      f64,
+    pub right_weight:     f64,
+    /// The multiplier of the score of a match on an older right word in the history.
+    pub history_weight:   f64,
+    /// The maximum edit distance of fuzzy matches. It is also limited to a third of the length of the query. Set it to `0` to disable fuzzy matching.
+    pub max_distance:     usize,
+    /// The maximum count of results.
+    pub limit:            Option<usize>,
+}
+
+impl Default for SearchOptions {
+    #[inline]
+    fn default() -> Self {
+        SearchOptions {
+            exact_weight:     8.0,
+            prefix_weight:    4.0,
+            substring_weight: 2.0,
+            fuzzy_weight:     1.0,
+            left_weight:      3.0,
+            right_weight:     1.0,
+            history_weight:   0.5,
+            max_distance:     2,
+            limit:            None,
+        }
+    }
+}
+
+/// An entry found by `Dictionary::search`, with its score.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SearchResult {
+    /// The index of the entry.
+    pub index:  usize,
+    pub kind:   MatchKind,
+    /// The best matched word of the entry.
+    pub target: MatchTarget,
+    pub score:  f64,
+}
+
+impl Dictionary {
+    /// Search left words by a query. Every matched entry is yielded once, in order.
+    pub fn search_left<S: AsRef<str>>(&self, query: S) -> impl Iterator<Item = Match> + '_ {
+        let query = Query::new(query.as_ref());
+
+        self.left.iter().enumerate().filter_map(move |(index, left)| {
+            let kind = if query.is_exact(left) {
+                MatchKind::Exact
+            } else if query.is_substring(left) {
+                MatchKind::Substring
+            } else {
+                return None;
+            };
+
+            Some(Match {
+                index,
+                kind,
+                target: MatchTarget::Left,
+            })
+        })
+    }
+
+    /// Search right words, including the history, by a query. Every matched entry is yielded once, in order. Exact matches are preferred, and then newer right words.
+    pub fn search_right<S: AsRef<str>>(&self, query: S) -> impl Iterator<Item = Match> + '_ {
+        let query = Query::new(query.as_ref());
+
+        self.right.iter().enumerate().filter_map(move |(index, right)| {
+            let (history_index, kind) = match right.iter().rposition(|s| query.is_exact(s)) {
+                Some(history_index) => (history_index, MatchKind::Exact),
+                None => (right.iter().rposition(|s| query.is_substring(s))?, MatchKind::Substring),
+            };
+
+            Some(Match {
+                index,
+                kind,
+                target: MatchTarget::Right {
+                    history_index,
+                },
+            })
+        })
+    }
+
+    /// Search both left words and right words by a query, and rank the entries by their best scores. Entries with equal scores stay in order.
+    pub fn search<S: AsRef<str>>(&self, query: S, options: &SearchOptions) -> Vec<SearchResult> {
+        let query = query.as_ref();
+
+        if query.is_empty() {
+            return Vec::new();
+        }
+
+        let query = Query::new(query);
+
+        let score = |kind: MatchKind, distance: usize| match kind {
+            MatchKind::Exact => options.exact_weight,
+            MatchKind::Prefix => options.prefix_weight,
+            MatchKind::Substring => options.substring_weight,
+            MatchKind::Fuzzy => options.fuzzy_weight / distance as f64,
+        };
+
+        let mut results: Vec<SearchResult> = Vec::new();
+
+        for (index, (left, right)) in self.iter().enumerate() {
+            let mut best: Option<SearchResult> = None;
+
+            let mut candidate = |kind: MatchKind, target: MatchTarget, score: f64| {
+                if best.map_or(true, |b| score > b.score) {
+                    best = Some(SearchResult {
+                        index,
+                        kind,
+                        target,
+                        score,
+                    });
+                }
+            };
+
+            if let Some((kind, distance)) = query.match_word(left, options.max_distance) {
+                candidate(kind, MatchTarget::Left, score(kind, distance) * options.left_weight);
+            }
+
+            for (history_index, s) in right.iter().enumerate().rev() {
+                if let Some((kind, distance)) = query.match_word(s, options.max_distance) {
+                    let mut score = score(kind, distance) * options.right_weight;
+
+                    if history_index + 1 < right.len() {
+                        score *= options.history_weight;
+                    }
+
+                    candidate(
+                        kind,
+                        MatchTarget::Right {
+                            history_index,
+                        },
+                        score,
+                    );
+                }
+            }
+
+            if let Some(result) = best {
+                results.push(result);
+            }
+        }
+
+        results.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(std::cmp::Ordering::Equal));
+
+        if let Some(limit) = options.limit {
+            results.truncate(limit);
+        }
+
+        results
+    }
+}
@@ -0,0 +1,281 @@
+use std::{
+    collections::HashMap,
+    io::{self, ErrorKind, Read, Write},
+};
+
+use crate::{Dictionary, FormatError, TermIssue};
+
+/// The kinds of Bethesda string tables.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StringTableKind {
+    /// `.STRINGS` files, whose strings are null-terminated.
+    Strings,
+    /// `.DLSTRINGS` files, whose strings are prefixed with their lengths.
+    DlStrings,
+    /// `.ILSTRINGS` files, whose strings are prefixed with their lengths.
+    IlStrings,
+}
+
+impl StringTableKind {
+    /// Get the kind by a file extension, ignoring case.
+    #[inline]
+    pub fn from_extension<S: AsRef<str>>(extension: S) -> Option<StringTableKind> {
+        let extension = extension.as_ref();
+
+        if extension.eq_ignore_ascii_case("strings") {
+            Some(StringTableKind::Strings)
+        } else if extension.eq_ignore_ascii_case("dlstrings") {
+            Some(StringTableKind::DlStrings)
+        } else if extension.eq_ignore_ascii_case("ilstrings") {
+            Some(StringTableKind::IlStrings)
+        } else {
+            None
+        }
+    }
+
+    #[inline]
+    fn length_prefixed(self) -> bool {
+        self != StringTableKind::Strings
+    }
+}
+
+/// The text encodings of string tables. The original English tables use Windows-1252, while many localized tables use UTF-8.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum StringsEncoding {
+    Utf8,
+    Windows1252,
+}
+
+/// The characters of Windows-1252 from `0x80` to `0x9F`. Undefined bytes are mapped to the C1 control characters, like what Windows does.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{81}', '\u{201A}', '\u{192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{2C6}', '\u{2030}', '\u{160}', '\u{2039}', '\u{152}', '\u{8D}', '\u{17D}', '\u{8F}',
+    '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{2DC}', '\u{2122}', '\u{161}', '\u{203A}', '\u{153}', '\u{9D}', '\u{17E}', '\u{178}',
+];
+
+impl StringsEncoding {
+    fn decode(self, bytes: &[u8]) -> Option<String> {
+        match self {
+            StringsEncoding::Utf8 => String::from_utf8(bytes.to_vec()).ok(),
+            StringsEncoding::Windows1252 => Some(
+                bytes
+                    .iter()
+                    .map(|&b| match b {
+                        0x80..=0x9F => WINDOWS_1252_HIGH[(b - 0x80) as usize],
+                        _ => b as char,
+                    })
+                    .collect(),
+            ),
+        }
+    }
+
+    fn encode(self, s: &str) -> Option<Vec<u8>> {
+        match self {
+            StringsEncoding::Utf8 => Some(s.as_bytes().to_vec()),
+            StringsEncoding::Windows1252 => s
+                .chars()
+                .map(|c| match c as u32 {
+                    0..=0x7F | 0xA0..=0xFF => Some(c as u8),
+                    _ => WINDOWS_1252_HIGH.iter().position(|&h| h == c).map(|i| 0x80 + i as u8),
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StringTableEntry {
+    pub id:   u32,
+    pub text: String,
+}
+
+/// A Bethesda string table (`.STRINGS`, `.DLSTRINGS` or `.ILSTRINGS`), used by games such as Skyrim and Fallout 4.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StringTable {
+    pub kind:    StringTableKind,
+    /// Entries in directory order.
+    pub entries: Vec<StringTableEntry>,
+}
+
+/// An issue found in a translated string table.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct StringTableIssue {
+    pub id:    u32,
+    pub issue: TermIssue,
+}
+
+#[inline]
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    data.get(offset..offset + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+impl StringTable {
+    /// Read a string table. Since the format is binary, the `line` of a `FormatError::Malformed` error is always `0`.
+    pub fn read<R: Read>(
+        mut reader: R,
+        kind: StringTableKind,
+        encoding: StringsEncoding,
+    ) -> Result<StringTable, FormatError> {
+        let mut data = Vec::new();
+
+        reader.read_to_end(&mut data)?;
+
+        let malformed = |reason: String| FormatError::Malformed {
+            line: 0,
+            reason,
+        };
+
+        let (count, data_size) = match (read_u32(&data, 0), read_u32(&data, 4)) {
+            (Some(count), Some(data_size)) => (count as usize, data_size as usize),
+            _ => return Err(malformed(String::from("the header is truncated"))),
+        };
+
+        let data_start = 8 + count * 8;
+
+        if data.len() < data_start + data_size {
+            return Err(malformed(String::from("the file is truncated")));
+        }
+
+        let string_data = &data[data_start..data_start + data_size];
+
+        let mut entries = Vec::with_capacity(count);
+
+        for i in 0..count {
+            let id = read_u32(&data, 8 + i * 8).unwrap();
+            let offset = read_u32(&data, 12 + i * 8).unwrap() as usize;
+
+            let bad_string =
+                || malformed(format!("the string {} at offset {} is broken", id, offset));
+
+            let bytes = if kind.length_prefixed() {
+                let length = read_u32(string_data, offset).ok_or_else(bad_string)? as usize;
+
+                let bytes =
+                    string_data.get(offset + 4..offset + 4 + length).ok_or_else(bad_string)?;
+
+                // the length includes the null terminator
+                bytes.strip_suffix(&[0]).unwrap_or(bytes)
+            } else {
+                let bytes = string_data.get(offset..).ok_or_else(bad_string)?;
+
+                let end = bytes.iter().position(|&b| b == 0).ok_or_else(bad_string)?;
+
+                &bytes[..end]
+            };
+
+            let text = encoding.decode(bytes).ok_or_else(bad_string)?;
+
+            entries.push(StringTableEntry {
+                id,
+                text,
+            });
+        }
+
+        Ok(StringTable {
+            kind,
+            entries,
+        })
+    }
+
+    /// Get the text of a string by its ID.
+    #[inline]
+    pub fn get(&self, id: u32) -> Option<&str> {
+        self.entries.iter().find(|entry| entry.id == id).map(|entry| entry.text.as_str())
+    }
+
+    /// Write this string table. Identical strings share their data. An `InvalidData` error is returned if a string cannot be encoded.
+    pub fn write_to<W: Write>(
+        &self,
+        mut writer: W,
+        encoding: StringsEncoding,
+    ) -> Result<(), io::Error> {
+        let mut directory = Vec::with_capacity(self.entries.len() * 8);
+        let mut string_data: Vec<u8> = Vec::new();
+        let mut offsets: HashMap<&str, u32> = HashMap::new();
+
+        for entry in self.entries.iter() {
+            let offset = match offsets.get(entry.text.as_str()) {
+                Some(offset) => *offset,
+                None => {
+                    let offset = string_data.len() as u32;
+
+                    let bytes = encoding.encode(&entry.text).ok_or_else(|| {
+                        io::Error::new(
+                            ErrorKind::InvalidData,
+                            format!("the string {} cannot be encoded", entry.id),
+                        )
+                    })?;
+
+                    if self.kind.length_prefixed() {
+                        string_data.extend_from_slice(&(bytes.len() as u32 + 1).to_le_bytes());
+                    }
+
+                    string_data.extend_from_slice(&bytes);
+                    string_data.push(0);
+
+                    offsets.insert(&entry.text, offset);
+
+                    offset
+                },
+            };
+
+            directory.extend_from_slice(&entry.id.to_le_bytes());
+            directory.extend_from_slice(&offset.to_le_bytes());
+        }
+
+        writer.write_all(&(self.entries.len() as u32).to_le_bytes())?;
+        writer.write_all(&(string_data.len() as u32).to_le_bytes())?;
+        writer.write_all(&directory)?;
+        writer.write_all(&string_data)?;
+
+        Ok(())
+    }
+}
+
+impl Dictionary {
+    /// Transform every string in a string table. Return the count of changed strings.
+    pub fn transform_string_table(&self, table: &mut StringTable) -> usize {
+        let mut count = 0;
+
+        for entry in table.entries.iter_mut() {
+            let text = self.transform(&entry.text);
+
+            if text != entry.text {
+                entry.text = text;
+
+                count += 1;
+            }
+        }
+
+        count
+    }
+
+    /// Check the terms of every string in a translated string table against the string with the same ID in a source string table. See `check_terms`.
+    pub fn check_string_table(
+        &self,
+        source: &StringTable,
+        target: &StringTable,
+    ) -> Vec<StringTableIssue> {
+        let target: HashMap<u32, &str> =
+            target.entries.iter().map(|entry| (entry.id, entry.text.as_str())).collect();
+
+        let mut issues = Vec::new();
+
+        for entry in source.entries.iter() {
+            let target_text = match target.get(&entry.id) {
+                Some(target_text) => target_text,
+                None => continue,
+            };
+
+            for issue in self.check_terms(&entry.text, target_text) {
+                issues.push(StringTableIssue {
+                    id: entry.id,
+                    issue,
+                });
+            }
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,469 @@
+use std::{
+    collections::HashMap,
+    io::{Read, Write},
+    ops::Range,
+};
+
+use crate::{markup::character_reference_len, Dictionary, FormatError, TermIssue};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum SubtitleFormat {
+    /// SubRip `.srt` files.
+    Srt,
+    /// Advanced SubStation Alpha `.ass` and SubStation Alpha `.ssa` files. Only `Dialogue` events are used.
+    Ass,
+    /// WebVTT `.vtt` files.
+    WebVtt,
+}
+
+impl SubtitleFormat {
+    /// Get the format by a file extension, ignoring case.
+    #[inline]
+    pub fn from_extension<S: AsRef<str>>(extension: S) -> Option<SubtitleFormat> {
+        let extension = extension.as_ref();
+
+        if extension.eq_ignore_ascii_case("srt") {
+            Some(SubtitleFormat::Srt)
+        } else if extension.eq_ignore_ascii_case("ass") || extension.eq_ignore_ascii_case("ssa") {
+            Some(SubtitleFormat::Ass)
+        } else if extension.eq_ignore_ascii_case("vtt") {
+            Some(SubtitleFormat::WebVtt)
+        } else {
+            None
+        }
+    }
+}
+
+/// A subtitle cue.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubtitleCue {
+    /// The 1-based position of the cue in the file.
+    pub index: usize,
+    /// The line where the text starts.
+    pub line:  usize,
+    /// The start timestamp as written in the file.
+    pub start: String,
+    /// The end timestamp as written in the file.
+    pub end:   String,
+    /// The text without styling tags. Lines are joined by `\n`.
+    pub text:  String,
+}
+
+/// An issue found in a translated subtitle file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct SubtitleIssue {
+    /// The 1-based position of the cue in the source file.
+    pub index: usize,
+    /// The start timestamp of the cue in the source file.
+    pub start: String,
+    /// The line of the cue text in the source file.
+    pub line:  usize,
+    pub issue: TermIssue,
+}
+
+/// A line of cue text in a subtitle file.
+struct CueLine<'a> {
+    index: usize,
+    line:  usize,
+    start: &'a str,
+    end:   &'a str,
+    range: Range<usize>,
+}
+
+/// Split a file into lines with their byte offsets. Line terminators are excluded.
+fn lines_with_offsets(s: &str) -> Vec<(usize, &str)> {
+    let mut offset = 0;
+
+    s.split_inclusive('\n')
+        .map(|raw_line| {
+            let line_offset = offset;
+
+            offset += raw_line.len();
+
+            (line_offset, raw_line.trim_end_matches(['\n', '\r']))
+        })
+        .collect()
+}
+
+/// Split a timing line like `00:00:01,000 --> 00:00:02,500 X1:0` into the start and end timestamps.
+#[inline]
+fn split_timing(timing: &str) -> (&str, &str) {
+    let (start, end) = timing.split_once("-->").unwrap();
+
+    let end = end.trim_start();
+
+    (start.trim(), &end[..end.find(char::is_whitespace).unwrap_or(end.len())])
+}
+
+/// Find the cue text lines of an SRT or WebVTT file. Both are made of blocks separated by blank lines.
+fn block_cue_lines(s: &str, format: SubtitleFormat) -> Result<Vec<CueLine<'_>>, FormatError> {
+    let lines = lines_with_offsets(s);
+
+    let mut cue_lines = Vec::new();
+
+    let mut i = 0;
+
+    if format == SubtitleFormat::WebVtt {
+        if !lines
+            .first()
+            .map_or(false, |(_, line)| line.trim_start_matches('\u{FEFF}').starts_with("WEBVTT"))
+        {
+            return Err(FormatError::Malformed {
+                line:   1,
+                reason: String::from("the file does not start with WEBVTT"),
+            });
+        }
+
+        // skip the header block
+        while i < lines.len() && !lines[i].1.trim().is_empty() {
+            i += 1;
+        }
+    }
+
+    let mut index = 0;
+
+    while i < lines.len() {
+        if lines[i].1.trim().is_empty() {
+            i += 1;
+            continue;
+        }
+
+        let block_start = i;
+
+        while i < lines.len() && !lines[i].1.trim().is_empty() {
+            i += 1;
+        }
+
+        let block = &lines[block_start..i];
+
+        if format == SubtitleFormat::WebVtt
+            && ["NOTE", "STYLE", "REGION"]
+                .iter()
+                .any(|k| block[0].1 == *k || block[0].1.starts_with(&format!("{} ", k)))
+        {
+            continue;
+        }
+
+        // the cue number or identifier is optional
+        let timing = match block.iter().take(2).position(|(_, line)| line.contains("-->")) {
+            Some(timing) => timing,
+            None => {
+                return Err(FormatError::Malformed {
+                    line:   block_start + 1,
+                    reason: String::from("a cue has no timing"),
+                })
+            },
+        };
+
+        index += 1;
+
+        let (start, end) = split_timing(block[timing].1);
+
+        for (j, (offset, line)) in block.iter().enumerate().skip(timing + 1) {
+            cue_lines.push(CueLine {
+                index,
+                line: block_start + j + 1,
+                start,
+                end,
+                range: *offset..offset + line.len(),
+            });
+        }
+    }
+
+    Ok(cue_lines)
+}
+
+/// Find the text of `Dialogue` events in an ASS or SSA file. The fields are decided by the `Format` line of the `[Events]` section.
+fn ass_cue_lines(s: &str) -> Result<Vec<CueLine<'_>>, FormatError> {
+    let mut cue_lines = Vec::new();
+
+    let mut in_events = false;
+
+    // the default format of ASS events
+    let mut field_count = 10;
+    let mut start_field = 1;
+    let mut end_field = 2;
+
+    let mut index = 0;
+
+    for (i, (offset, line)) in lines_with_offsets(s).into_iter().enumerate() {
+        let trimmed = line.trim_start_matches('\u{FEFF}').trim();
+
+        if trimmed.starts_with('[') {
+            in_events = trimmed.eq_ignore_ascii_case("[events]");
+            continue;
+        }
+
+        if !in_events {
+            continue;
+        }
+
+        if let Some(format) = trimmed.strip_prefix("Format:") {
+            let fields: Vec<&str> = format.split(',').map(str::trim).collect();
+
+            let find = |name: &str| fields.iter().position(|f| f.eq_ignore_ascii_case(name));
+
+            match (find("Start"), find("End"), find("Text")) {
+                (Some(start), Some(end), Some(text)) if text == fields.len() - 1 => {
+                    field_count = fields.len();
+                    start_field = start;
+                    end_field = end;
+                },
+                _ => {
+                    return Err(FormatError::Malformed {
+                        line:   i + 1,
+                        reason: String::from("the event format has no Start, End or Text fields"),
+                    })
+                },
+            }
+
+            continue;
+        }
+
+        let dialogue = match line.strip_prefix("Dialogue:") {
+            Some(dialogue) => dialogue,
+            None => continue,
+        };
+
+        // the text is the last field, and it can contain commas
+        let fields: Vec<&str> = dialogue.splitn(field_count, ',').collect();
+
+        if fields.len() < field_count {
+            return Err(FormatError::Malformed {
+                line:   i + 1,
+                reason: String::from("a dialogue event has too few fields"),
+            });
+        }
+
+        index += 1;
+
+        let text = fields[field_count - 1];
+
+        cue_lines.push(CueLine {
+            index,
+            line: i + 1,
+            start: fields[start_field].trim(),
+            end: fields[end_field].trim(),
+            range: offset + line.len() - text.len()..offset + line.len(),
+        });
+    }
+
+    Ok(cue_lines)
+}
+
+#[inline]
+fn cue_lines(s: &str, format: SubtitleFormat) -> Result<Vec<CueLine<'_>>, FormatError> {
+    match format {
+        SubtitleFormat::Srt | SubtitleFormat::WebVtt => block_cue_lines(s, format),
+        SubtitleFormat::Ass => ass_cue_lines(s),
+    }
+}
+
+/// Split cue text into ranges of text and ranges of styling. Each range comes with whether it is styling.
+///
+/// Styling is `{...}` override blocks (like `{\i1}` and `{\an8}`), `<...>` tags in SRT and WebVTT (including WebVTT timestamps), `\N`, `\n` and `\h` in ASS, and character references in WebVTT.
+fn cue_segments(s: &str, format: SubtitleFormat) -> Vec<(Range<usize>, bool)> {
+    let bytes = s.as_bytes();
+
+    let mut segments: Vec<(Range<usize>, bool)> = Vec::new();
+
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let styling_len = match bytes[i] {
+            b'{' => bytes[i..].iter().position(|&b| b == b'}').map(|p| p + 1),
+            b'<' if format != SubtitleFormat::Ass => match bytes.get(i + 1) {
+                Some(b) if b.is_ascii_alphanumeric() || *b == b'/' => {
+                    bytes[i..].iter().position(|&b| b == b'>').map(|p| p + 1)
+                },
+                _ => None,
+            },
+            b'\\' if format == SubtitleFormat::Ass => match bytes.get(i + 1) {
+                Some(b'N' | b'n' | b'h') => Some(2),
+                _ => None,
+            },
+            b'&' if format == SubtitleFormat::WebVtt => character_reference_len(bytes, i),
+            _ => None,
+        };
+
+        let (len, styling) = match styling_len {
+            Some(len) => (len, true),
+            None => (s[i..].chars().next().unwrap().len_utf8(), false),
+        };
+
+        match segments.last_mut() {
+            Some((last, last_styling)) if *last_styling == styling => last.end = i + len,
+            _ => segments.push((i..i + len, styling)),
+        }
+
+        i += len;
+    }
+
+    segments
+}
+
+/// Get the plain text of cue text.
+fn plain_text(s: &str, format: SubtitleFormat) -> String {
+    let mut text = String::with_capacity(s.len());
+
+    for (range, styling) in cue_segments(s, format) {
+        if !styling {
+            text.push_str(&s[range]);
+            continue;
+        }
+
+        let styling = &s[range];
+
+        match format {
+            SubtitleFormat::Ass => {
+                // override blocks are dropped
+                let mut rest = styling;
+
+                while let Some(i) = rest.find('\\') {
+                    match rest.as_bytes().get(i + 1) {
+                        Some(b'N' | b'n') => text.push('\n'),
+                        Some(b'h') => text.push(' '),
+                        _ => (),
+                    }
+
+                    rest = &rest[i + 1..];
+                }
+            },
+            SubtitleFormat::WebVtt if styling.starts_with('&') => {
+                let mut rest = styling;
+
+                while let Some(i) = rest.find('&') {
+                    let end = rest[i..].find(';').map_or(rest.len(), |p| i + p + 1);
+
+                    match &rest[i..end] {
+                        "&amp;" => text.push('&'),
+                        "&lt;" => text.push('<'),
+                        "&gt;" => text.push('>'),
+                        "&nbsp;" => text.push(' '),
+                        _ => (),
+                    }
+
+                    rest = &rest[end..];
+                }
+            },
+            _ => (),
+        }
+    }
+
+    text
+}
+
+/// Read the cues of a subtitle file.
+pub fn read_subtitles<R: Read>(
+    mut reader: R,
+    format: SubtitleFormat,
+) -> Result<Vec<SubtitleCue>, FormatError> {
+    let mut s = String::new();
+
+    reader.read_to_string(&mut s)?;
+
+    let mut cues: Vec<SubtitleCue> = Vec::new();
+
+    for cue_line in cue_lines(&s, format)? {
+        let text = plain_text(&s[cue_line.range], format);
+
+        match cues.last_mut() {
+            Some(last) if last.index == cue_line.index => {
+                last.text.push('\n');
+                last.text.push_str(&text);
+            },
+            _ => cues.push(SubtitleCue {
+                index: cue_line.index,
+                line: cue_line.line,
+                start: String::from(cue_line.start),
+                end: String::from(cue_line.end),
+                text,
+            }),
+        }
+    }
+
+    Ok(cues)
+}
+
+impl Dictionary {
+    /// Transform the cue text in a subtitle file. Timing, styling tags, cue numbers and everything else are kept. Return the count of changed cues.
+    pub fn transform_subtitles<R: Read, W: Write>(
+        &self,
+        mut reader: R,
+        mut writer: W,
+        format: SubtitleFormat,
+    ) -> Result<usize, FormatError> {
+        let mut s = String::new();
+
+        reader.read_to_string(&mut s)?;
+
+        let mut output = String::with_capacity(s.len());
+
+        let mut last = 0;
+        let mut last_changed_index = 0;
+        let mut count = 0;
+
+        for cue_line in cue_lines(&s, format)? {
+            let text = &s[cue_line.range.clone()];
+
+            output.push_str(&s[last..cue_line.range.start]);
+
+            for (range, styling) in cue_segments(text, format) {
+                if styling {
+                    output.push_str(&text[range]);
+                    continue;
+                }
+
+                let transformed = self.transform(&text[range.clone()]);
+
+                if transformed != text[range] && last_changed_index != cue_line.index {
+                    last_changed_index = cue_line.index;
+                    count += 1;
+                }
+
+                output.push_str(&transformed);
+            }
+
+            last = cue_line.range.end;
+        }
+
+        output.push_str(&s[last..]);
+
+        writer.write_all(output.as_bytes())?;
+
+        Ok(count)
+    }
+
+    /// Check the terms of every cue in a translated subtitle file against the cue at the same position in a source subtitle file. Styling tags are ignored. See `check_terms`.
+    pub fn check_subtitles<S: Read, T: Read>(
+        &self,
+        source: S,
+        target: T,
+        format: SubtitleFormat,
+    ) -> Result<Vec<SubtitleIssue>, FormatError> {
+        let source = read_subtitles(source, format)?;
+
+        let target: HashMap<usize, String> =
+            read_subtitles(target, format)?.into_iter().map(|cue| (cue.index, cue.text)).collect();
+
+        let mut issues = Vec::new();
+
+        for cue in source {
+            let target_text = match target.get(&cue.index) {
+                Some(target_text) => target_text,
+                None => continue,
+            };
+
+            for issue in self.check_terms(&cue.text, target_text) {
+                issues.push(SubtitleIssue {
+                    index: cue.index,
+                    start: cue.start.clone(),
+                    line: cue.line,
+                    issue,
+                });
+            }
+        }
+
+        Ok(issues)
+    }
+}
@@ -0,0 +1,293 @@
+use std::{
+    collections::HashMap,
+    io::{self, Read, Write},
+};
+
+use crate::{Dictionary, Entry, FormatError, ImportError, ImportMode, ImportReport};
+
+/// Spreadsheet formats for importing and exporting a dictionary.
+///
+/// Columns are `left`, `right` (the current right word), `history` (all right words joined by `-->`) and an optional `metadata`. Fields are quoted in the RFC 4180 way.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum TableFormat {
+    /// Comma-separated values.
+    Csv,
+    /// Tab-separated values.
+    Tsv,
+}
+
+impl TableFormat {
+    #[inline]
+    fn delimiter(self) -> char {
+        match self {
+            TableFormat::Csv => ',',
+            TableFormat::Tsv => '\t',
+        }
+    }
+}
+
+/// A row of a CSV/TSV table.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TableRecord {
+    /// The line where the row starts. It is `0` for records which are not read from a table.
+    pub line:     usize,
+    pub entry:    Entry,
+    pub metadata: Option<String>,
+}
+
+/// The result of `Dictionary::import_table`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TableImportReport {
+    pub report:   ImportReport,
+    /// The metadata of the rows by their left words. Rows without metadata are not included.
+    pub metadata: HashMap<String, String>,
+}
+
+const HEADER: [&str; 4] = ["left", "right", "history", "metadata"];
+
+/// Whether a row is a header row, which has the column names with or without `metadata`.
+#[inline]
+fn is_header(fields: &[String]) -> bool {
+    (fields.len() == HEADER.len() || fields.len() == HEADER.len() - 1)
+        && fields.iter().zip(HEADER).all(|(field, name)| field.trim().eq_ignore_ascii_case(name))
+}
+
+/// Split a table into rows of fields. Each row comes with the line where it starts.
+fn parse_rows(s: &str, delimiter: char) -> Result<Vec<(usize, Vec<String>)>, FormatError> {
+    let mut rows = Vec::new();
+
+    let mut chars = s.chars().peekable();
+
+    let mut line = 1;
+    let mut row_line = 1;
+    let mut row: Vec<String> = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut field_started = false;
+
+    while let Some(c) = chars.next() {
+        if quoted {
+            match c {
+                '"' => {
+                    if chars.peek() == Some(&'"') {
+                        chars.next();
+                        field.push('"');
+                    } else {
+                        quoted = false;
+                    }
+                },
+                '\n' => {
+                    line += 1;
+                    field.push(c);
+                },
+                _ => field.push(c),
+            }
+
+            continue;
+        }
+
+        match c {
+            '"' if !field_started => {
+                quoted = true;
+                field_started = true;
+            },
+            '\r' if chars.peek() == Some(&'\n') => (),
+            '\n' => {
+                row.push(std::mem::take(&mut field));
+
+                if !(row.len() == 1 && row[0].trim().is_empty()) {
+                    rows.push((row_line, std::mem::take(&mut row)));
+                } else {
+                    row.clear();
+                }
+
+                line += 1;
+                row_line = line;
+                field_started = false;
+            },
+            _ if c == delimiter => {
+                row.push(std::mem::take(&mut field));
+                field_started = false;
+            },
+            _ => {
+                field.push(c);
+                field_started = true;
+            },
+        }
+    }
+
+    if quoted {
+        return Err(FormatError::Malformed {
+            line:   row_line,
+            reason: String::from("a quoted field is not closed"),
+        });
+    }
+
+    row.push(field);
+
+    if !(row.len() == 1 && row[0].trim().is_empty()) {
+        rows.push((row_line, row));
+    }
+
+    Ok(rows)
+}
+
+/// Read CSV/TSV records. A first row which is the header row is skipped.
+pub fn read_table<R: Read>(
+    mut reader: R,
+    format: TableFormat,
+) -> Result<Vec<TableRecord>, FormatError> {
+    let mut s = String::new();
+
+    reader.read_to_string(&mut s)?;
+
+    let s = s.strip_prefix('\u{FEFF}').unwrap_or(&s);
+
+    let mut records = Vec::new();
+
+    for (i, (line, fields)) in parse_rows(s, format.delimiter())?.into_iter().enumerate() {
+        if i == 0 && is_header(&fields) {
+            continue;
+        }
+
+        if fields.len() > HEADER.len() {
+            return Err(FormatError::Malformed {
+                line,
+                reason: format!("expected at most {} fields, found {}", HEADER.len(), fields.len()),
+            });
+        }
+
+        let mut fields = fields.into_iter();
+
+        let left = fields.next().unwrap();
+        let right_string = fields.next().unwrap_or_default();
+        let history = fields.next().unwrap_or_default();
+        let metadata = fields.next().filter(|s| !s.is_empty());
+
+        let mut right: Vec<String> = if history.trim().is_empty() {
+            Vec::new()
+        } else {
+            history.split("-->").map(|s| String::from(s.trim())).collect()
+        };
+
+        let right_string = right_string.trim();
+
+        if !right_string.is_empty() && right.last().map(|s| s.as_str()) != Some(right_string) {
+            right.push(String::from(right_string));
+        }
+
+        records.push(TableRecord {
+            line,
+            entry: Entry::new(left, right),
+            metadata,
+        });
+    }
+
+    Ok(records)
+}
+
+fn write_field<W: Write>(writer: &mut W, s: &str, delimiter: char) -> Result<(), io::Error> {
+    let need_quotes = s.contains(delimiter)
+        || s.contains(['"', '\r', '\n', '='])
+        || s.contains("-->")
+        || s.starts_with(char::is_whitespace)
+        || s.ends_with(char::is_whitespace);
+
+    if need_quotes {
+        write!(writer, "\"{}\"", s.replace('"', "\"\""))
+    } else {
+        writer.write_all(s.as_bytes())
+    }
+}
+
+/// Write CSV/TSV records with a header row. The `metadata` column is written only if any record has metadata.
+pub fn write_table<W: Write>(
+    mut writer: W,
+    format: TableFormat,
+    records: &[TableRecord],
+) -> Result<(), io::Error> {
+    let delimiter = format.delimiter();
+
+    let columns = if records.iter().any(|record| record.metadata.is_some()) {
+        HEADER.len()
+    } else {
+        HEADER.len() - 1
+    };
+
+    for (i, name) in HEADER[..columns].iter().enumerate() {
+        if i > 0 {
+            write!(writer, "{}", delimiter)?;
+        }
+
+        writer.write_all(name.as_bytes())?;
+    }
+
+    writer.write_all(b"\r\n")?;
+
+    for record in records {
+        write_field(&mut writer, &record.entry.left, delimiter)?;
+        write!(writer, "{}", delimiter)?;
+        write_field(&mut writer, record.entry.get_right().unwrap_or_default(), delimiter)?;
+        write!(writer, "{}", delimiter)?;
+        write_field(&mut writer, &record.entry.right.join(" --> "), delimiter)?;
+
+        if columns == HEADER.len() {
+            write!(writer, "{}", delimiter)?;
+            write_field(&mut writer, record.metadata.as_deref().unwrap_or_default(), delimiter)?;
+        }
+
+        writer.write_all(b"\r\n")?;
+    }
+
+    Ok(())
+}
+
+impl Dictionary {
+    /// Export all entries to a CSV/TSV table, with the metadata of the entries by their left words.
+    #[inline]
+    pub fn export_table<W: Write>(
+        &self,
+        writer: W,
+        format: TableFormat,
+        metadata: &HashMap<String, String>,
+    ) -> Result<(), io::Error> {
+        let records: Vec<TableRecord> = (0..self.count())
+            .map(|index| TableRecord {
+                line:     0,
+                entry:    self.get_entry(index).unwrap(),
+                metadata: metadata.get(&self.left[index]).cloned(),
+            })
+            .collect();
+
+        write_table(writer, format, &records)
+    }
+
+    /// Import entries from a CSV/TSV table with the same rules as `add_edit`. The `position` of each conflict is the line number in the table. The metadata is returned, since the dictionary does not store it.
+    #[inline]
+    pub fn import_table<R: Read>(
+        &mut self,
+        reader: R,
+        format: TableFormat,
+        mode: ImportMode,
+    ) -> Result<TableImportReport, ImportError> {
+        let records = read_table(reader, format)?;
+
+        let mut metadata = HashMap::new();
+        let mut entries = Vec::with_capacity(records.len());
+
+        for record in records {
+            if let Some(m) = record.metadata {
+                metadata.insert(record.entry.left.clone(), m);
+            }
+
+            entries.push((record.line, record.entry));
+        }
+
+        let report = self.import_entries_with_positions(entries, mode)?;
+
+        Ok(TableImportReport {
+            report,
+            metadata,
+        })
+    }
+}
@@ -0,0 +1,201 @@
+use std::io::{self, Read, Write};
+
+use crate::{
+    xml::{escape, XmlDocument, XmlElement},
+    Dictionary, Entry, FormatError, ImportError, ImportMode, ImportReport,
+};
+
+const PREFERRED_STATUS: &str = "preferredTerm-admn-sts";
+const DEPRECATED_STATUS: &str = "deprecatedTerm-admn-sts";
+
+/// Whether a `xml:lang` value matches a language, e.g. `en-US` matches `en`.
+#[inline]
+fn lang_matches(lang: &str, wanted: &str) -> bool {
+    lang.eq_ignore_ascii_case(wanted)
+        || (lang.len() > wanted.len()
+            && lang.as_bytes()[wanted.len()] == b'-'
+            && lang[..wanted.len()].eq_ignore_ascii_case(wanted))
+}
+
+/// Collect the terms of a `langSet` (TBX 2008) or a `langSec` (TBX 2019). Each term comes with whether it is deprecated or superseded.
+fn collect_terms(lang_set: &XmlElement) -> Vec<(String, bool)> {
+    let mut term_groups = Vec::new();
+
+    for e in lang_set.elements() {
+        if e.is("tig") || e.is("ntig") || e.is("termSec") {
+            term_groups.push(e);
+        }
+    }
+
+    if term_groups.is_empty() {
+        term_groups.push(lang_set);
+    }
+
+    let mut terms = Vec::new();
+
+    for group in term_groups {
+        let mut term_elements = Vec::new();
+        group.descendants("term", &mut term_elements);
+
+        let mut notes = Vec::new();
+        group.descendants("termNote", &mut notes);
+
+        let deprecated = notes.iter().any(|note| {
+            note.attribute("type") == Some("administrativeStatus") && {
+                let status = note.text();
+
+                status.starts_with("deprecated") || status.starts_with("superseded")
+            }
+        });
+
+        for term in term_elements {
+            let term = term.text();
+            let term = term.trim();
+
+            if !term.is_empty() {
+                terms.push((String::from(term), deprecated));
+            }
+        }
+    }
+
+    terms
+}
+
+/// Read entries from a TBX document. Both TBX-Basic 2008 (`martif`) and TBX 2019 (`tbx`) are accepted.
+///
+/// In each term entry, the first non-deprecated term of `source_lang` becomes the left word. The deprecated or superseded terms of `target_lang` become the older right words in document order, and the first non-deprecated term of `target_lang` becomes the current right word.
+pub fn read_tbx<R: Read>(
+    mut reader: R,
+    source_lang: &str,
+    target_lang: &str,
+) -> Result<Vec<Entry>, FormatError> {
+    let mut s = String::new();
+
+    reader.read_to_string(&mut s)?;
+
+    let document = XmlDocument::parse(&s)?;
+
+    let mut term_entries = Vec::new();
+    document.root().descendants("termEntry", &mut term_entries);
+    document.root().descendants("conceptEntry", &mut term_entries);
+
+    let mut entries = Vec::with_capacity(term_entries.len());
+
+    for term_entry in term_entries {
+        let mut left = None;
+        let mut deprecated_right = Vec::new();
+        let mut current_right = None;
+
+        for lang_set in term_entry.elements().filter(|e| e.is("langSet") || e.is("langSec")) {
+            let lang = lang_set.attribute("xml:lang").unwrap_or_default();
+
+            if lang_matches(lang, source_lang) {
+                let terms = collect_terms(lang_set);
+
+                if left.is_none() {
+                    left = terms
+                        .iter()
+                        .find(|(_, deprecated)| !deprecated)
+                        .or_else(|| terms.first())
+                        .map(|(term, _)| term.clone());
+                }
+            } else if lang_matches(lang, target_lang) {
+                for (term, deprecated) in collect_terms(lang_set) {
+                    if deprecated {
+                        deprecated_right.push(term);
+                    } else if current_right.is_none() {
+                        current_right = Some(term);
+                    }
+                }
+            }
+        }
+
+        let mut right = deprecated_right;
+
+        if let Some(current_right) = current_right {
+            right.push(current_right);
+        }
+
+        entries.push(Entry::new(left.unwrap_or_default(), right));
+    }
+
+    Ok(entries)
+}
+
+impl Dictionary {
+    /// Export all entries to a TBX-Basic (TBX 2008) document. The current right word of each entry is the preferred term, and older right words are deprecated terms.
+    pub fn export_tbx<W: Write>(
+        &self,
+        mut writer: W,
+        source_lang: &str,
+        target_lang: &str,
+    ) -> Result<(), io::Error> {
+        let source_lang = escape(source_lang, true);
+        let target_lang = escape(target_lang, true);
+
+        writeln!(writer, "<?xml version=\"1.0\" encoding=\"UTF-8\"?>")?;
+        writeln!(writer, "<!DOCTYPE martif SYSTEM \"TBXBasiccoreStructV02.dtd\">")?;
+        writeln!(writer, "<martif type=\"TBX-Basic\" xml:lang=\"{}\">", source_lang)?;
+        writeln!(writer, "  <martifHeader>")?;
+        writeln!(writer, "    <fileDesc>")?;
+        writeln!(writer, "      <sourceDesc>")?;
+        writeln!(writer, "        <p>word-dictionary</p>")?;
+        writeln!(writer, "      </sourceDesc>")?;
+        writeln!(writer, "    </fileDesc>")?;
+        writeln!(writer, "    <encodingDesc>")?;
+        writeln!(writer, "      <p type=\"XCSURI\">TBXBasicXCSV02.xcs</p>")?;
+        writeln!(writer, "    </encodingDesc>")?;
+        writeln!(writer, "  </martifHeader>")?;
+        writeln!(writer, "  <text>")?;
+        writeln!(writer, "    <body>")?;
+
+        for index in 0..self.count() {
+            writeln!(writer, "      <termEntry id=\"e{}\">", index + 1)?;
+            writeln!(writer, "        <langSet xml:lang=\"{}\">", source_lang)?;
+            writeln!(writer, "          <tig>")?;
+            writeln!(writer, "            <term>{}</term>", escape(&self.left[index], false))?;
+            writeln!(writer, "          </tig>")?;
+            writeln!(writer, "        </langSet>")?;
+            writeln!(writer, "        <langSet xml:lang=\"{}\">", target_lang)?;
+
+            let right = &self.right[index];
+            let last = right.len() - 1;
+
+            for (i, status) in std::iter::once((last, PREFERRED_STATUS))
+                .chain((0..last).map(|i| (i, DEPRECATED_STATUS)))
+            {
+                writeln!(writer, "          <tig>")?;
+                writeln!(writer, "            <term>{}</term>", escape(&right[i], false))?;
+                writeln!(
+                    writer,
+                    "            <termNote type=\"administrativeStatus\">{}</termNote>",
+                    status
+                )?;
+                writeln!(writer, "          </tig>")?;
+            }
+
+            writeln!(writer, "        </langSet>")?;
+            writeln!(writer, "      </termEntry>")?;
+        }
+
+        writeln!(writer, "    </body>")?;
+        writeln!(writer, "  </text>")?;
+        writeln!(writer, "</martif>")?;
+
+        Ok(())
+    }
+
+    /// Import entries from a TBX document with the same rules as `add_edit`. The `position` of each conflict is the index of the term entry. See `read_tbx`.
+    #[inline]
+    pub fn import_tbx<R: Read>(
+        &mut self,
+        reader: R,
+        source_lang: &str,
+        target_lang: &str,
+        mode: ImportMode,
+    ) -> Result<ImportReport, ImportError> {
+        let entries = read_tbx(reader, source_lang, target_lang)?;
+
+        Ok(self.import_entries(entries, mode)?)
+    }
+}
@@ -0,0 +1,217 @@
+use std::{
+    collections::HashMap,
+    fmt::{self, Display, Formatter},
+    fs::File,
+    io::Write,
+};
+
+use crate::{combine_histories, Dictionary, OperationKind, WriteError};
+
+/// An entry which is changed differently on both sides of a three-way merge. A `None` means the entry does not exist on that side.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct ThreeWayConflict {
+    pub left_string:         String,
+    /// The right words in the common ancestor.
+    pub base_right_strings:  Option<Vec<String>>,
+    /// Our right words.
+    pub right_strings:       Option<Vec<String>>,
+    /// Their right words.
+    pub other_right_strings: Option<Vec<String>>,
+}
+
+impl Display for ThreeWayConflict {
+    #[inline]
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), fmt::Error> {
+        match (&self.right_strings, &self.other_right_strings) {
+            (Some(right_strings), Some(other_right_strings)) => f.write_fmt(format_args!(
+                "the left string {:?} is mapped to {:?} by us and {:?} by them",
+                self.left_string,
+                right_strings.last().unwrap(),
+                other_right_strings.last().unwrap()
+            )),
+            (None, _) => f.write_fmt(format_args!(
+                "the left string {:?} is deleted by us and changed by them",
+                self.left_string
+            )),
+            (_, None) => f.write_fmt(format_args!(
+                "the left string {:?} is changed by us and deleted by them",
+                self.left_string
+            )),
+        }
+    }
+}
+
+impl ThreeWayConflict {
+    /// Write the conflict with markers like git does.
+    fn write_markers<W: Write>(&self, writer: &mut W) -> Result<(), WriteError> {
+        writeln!(writer, "<<<<<<< ours")?;
+
+        if let Some(right_strings) = &self.right_strings {
+            writeln!(writer, "{} = {}", self.left_string, right_strings.join(" --> "))?;
+        }
+
+        writeln!(writer, "=======")?;
+
+        if let Some(other_right_strings) = &self.other_right_strings {
+            writeln!(writer, "{} = {}", self.left_string, other_right_strings.join(" --> "))?;
+        }
+
+        write!(writer, ">>>>>>> theirs")?;
+
+        Ok(())
+    }
+}
+
+#[inline]
+fn index_map(dictionary: &Dictionary) -> HashMap<String, usize> {
+    dictionary
+        .left
+        .iter()
+        .enumerate()
+        .map(|(index, left)| (left.to_ascii_lowercase(), index))
+        .collect()
+}
+
+impl Dictionary {
+    /// Merge the changes from a common ancestor `base` to `other` into this dictionary, entry by entry. Left words are compared ignoring the case of ASCII letters.
+    ///
+    /// An entry changed on only one side takes that change. If both sides keep the same current right word, their histories are combined. Other entries changed on both sides are conflicts, which keep our version in this instance, and are written to the dictionary file between conflict markers like git does, so the file cannot be read until they are resolved.
+    pub fn merge_three_way(
+        &mut self,
+        base: &Dictionary,
+        other: &Dictionary,
+    ) -> Result<Vec<ThreeWayConflict>, WriteError> {
+        let base_indices = index_map(base);
+        let other_indices = index_map(other);
+
+        let base_right =
+            |key: &str| base_indices.get(key).map(|&index| base.right[index].as_slice());
+        let other_right =
+            |key: &str| other_indices.get(key).map(|&index| other.right[index].as_slice());
+
+        let snapshot = self.snapshot();
+
+        let mut conflicts = Vec::new();
+        let mut removed = Vec::new();
+
+        for index in 0..self.count() {
+            let key = self.left[index].to_ascii_lowercase();
+
+            let right = self.right[index].as_slice();
+            let (o, b) = (base_right(&key), other_right(&key));
+
+            if Some(right) == b || b == o {
+                continue;
+            }
+
+            if Some(right) == o {
+                match b {
+                    Some(b) => self.right[index] = b.to_vec(),
+                    None => removed.push(index),
+                }
+
+                continue;
+            }
+
+            match b {
+                Some(b) if right.last() == b.last() => {
+                    self.right[index] = combine_histories(right, b);
+                },
+                _ => conflicts.push(ThreeWayConflict {
+                    left_string:         self.left[index].clone(),
+                    base_right_strings:  o.map(|o| o.to_vec()),
+                    right_strings:       Some(right.to_vec()),
+                    other_right_strings: b.map(|b| b.to_vec()),
+                }),
+            }
+        }
+
+        for index in removed.into_iter().rev() {
+            self.left.remove(index);
+            self.right.remove(index);
+        }
+
+        let indices = index_map(self);
+
+        for (left, b) in other.iter() {
+            let key = left.to_ascii_lowercase();
+
+            if indices.contains_key(&key) {
+                continue;
+            }
+
+            match base_right(&key) {
+                // deleted by us
+                Some(o) if o == b => (),
+                Some(o) => conflicts.push(ThreeWayConflict {
+                    left_string:         String::from(left),
+                    base_right_strings:  Some(o.to_vec()),
+                    right_strings:       None,
+                    other_right_strings: Some(b.to_vec()),
+                }),
+                None => {
+                    self.left.push(String::from(left));
+                    self.right.push(b.to_vec());
+                },
+            }
+        }
+
+        self.rebuild_prefix_index();
+
+        self.record(OperationKind::ThreeWayMerge, snapshot);
+
+        self.write_data()?;
+
+        if !conflicts.is_empty() {
+            self.write_file_with_conflicts(&conflicts)?;
+        }
+
+        Ok(conflicts)
+    }
+
+    /// Write the sorted entries to the dictionary file, replacing conflicted entries by conflict markers.
+    fn write_file_with_conflicts(&self, conflicts: &[ThreeWayConflict]) -> Result<(), WriteError> {
+        let conflict_indices: HashMap<String, usize> = conflicts
+            .iter()
+            .enumerate()
+            .map(|(i, conflict)| (conflict.left_string.to_ascii_lowercase(), i))
+            .collect();
+
+        // (the sort key, the entry index or the conflict index)
+        let mut blocks: Vec<(String, Result<usize, usize>)> = Vec::new();
+
+        for (index, left) in self.left.iter().enumerate() {
+            let block = match conflict_indices.get(&left.to_ascii_lowercase()) {
+                Some(&i) => Err(i),
+                None => Ok(index),
+            };
+
+            blocks.push((left.to_uppercase(), block));
+        }
+
+        for (i, conflict) in conflicts.iter().enumerate() {
+            if conflict.right_strings.is_none() {
+                blocks.push((conflict.left_string.to_uppercase(), Err(i)));
+            }
+        }
+
+        blocks.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut file = File::create(&self.path)?;
+
+        for (i, (_, block)) in blocks.into_iter().enumerate() {
+            if i > 0 {
+                writeln!(file)?;
+            }
+
+            match block {
+                Ok(index) => {
+                    write!(file, "{} = {}", self.left[index], self.right[index].join(" --> "))?
+                },
+                Err(i) => conflicts[i].write_markers(&mut file)?,
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,378 @@
+use std::{borrow::Cow, collections::HashMap, ops::Range};
+
+use crate::{inflection, Dictionary, Inflection, Markup, MatchTarget};
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum TermIssueKind {
+    /// The translation contains none of the right words.
+    Missing,
+    /// The translation contains an older right word instead of the current one.
+    Outdated { found: String },
+}
+
+/// A term in a source text which is not translated with its current right word.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct TermIssue {
+    /// The index of the entry.
+    pub index:        usize,
+    pub left_string:  String,
+    /// The current right word, which is expected in the translation.
+    pub right_string: String,
+    pub kind:         TermIssueKind,
+}
+
+/// How the case of a right word is decided when it replaces a left word.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum CasePolicy {
+    /// Use the right word as it is.
+    #[default]
+    Keep,
+    /// Follow the case pattern of the matched text. An uppercase match makes the right word uppercase, a lowercase match makes it lowercase, and a title-case match capitalizes its first letter. Other patterns keep the right word as it is.
+    Preserve,
+}
+
+impl CasePolicy {
+    /// Adapt the case of a right word to the text matched by its left word.
+    pub fn apply<'a>(self, matched: &str, right: &'a str) -> Cow<'a, str> {
+        if self == CasePolicy::Keep {
+            return Cow::Borrowed(right);
+        }
+
+        let mut cased = matched.chars().filter(|c| c.is_lowercase() || c.is_uppercase());
+
+        let first_upper = match cased.next() {
+            Some(c) => c.is_uppercase(),
+            None => return Cow::Borrowed(right),
+        };
+
+        let mut rest_upper = false;
+        let mut rest_lower = false;
+
+        for c in cased {
+            if c.is_uppercase() {
+                rest_upper = true;
+            } else {
+                rest_lower = true;
+            }
+        }
+
+        match (first_upper, rest_upper, rest_lower) {
+            (true, true, false) => Cow::Owned(right.to_uppercase()),
+            (false, false, _) => Cow::Owned(right.to_lowercase()),
+            (true, false, _) => {
+                let mut chars = right.chars();
+
+                match chars.next() {
+                    Some(c) if c.is_lowercase() => {
+                        Cow::Owned(c.to_uppercase().chain(chars).collect())
+                    },
+                    _ => Cow::Borrowed(right),
+                }
+            },
+            _ => Cow::Borrowed(right),
+        }
+    }
+}
+
+/// Options of `Dictionary::transform_with_options`.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct TransformOptions {
+    /// The markup language of the text. Only human-visible text is transformed.
+    pub markup:           Markup,
+    pub case:             CasePolicy,
+    pub boundary:         BoundaryRule,
+    /// Boundary rules of specific entries, which override `boundary`. Keys are left words as they are in the dictionary.
+    pub entry_boundaries: HashMap<String, BoundaryRule>,
+    /// Whether to match simple English variants of left words, which are plurals, possessives and parts of hyphenated compounds. See `Inflection`.
+    pub inflection:       bool,
+}
+
+/// How matches of left words have to be delimited.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
+pub enum BoundaryRule {
+    /// Left words match anywhere, even inside other words.
+    #[default]
+    Substring,
+    /// Left words only match whole words. Boundaries are only required between letters or digits of space-delimited scripts, so text in scripts written without spaces, like Chinese, Japanese, Korean and Thai, still matches anywhere.
+    Word,
+}
+
+/// A left word found in a text.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Occurrence {
+    /// The byte range of the matched text.
+    pub range:      Range<usize>,
+    /// The index of the entry.
+    pub index:      usize,
+    /// The variant of the left word which is matched, if inflection-aware matching is enabled and the match is not the plain left word.
+    pub inflection: Option<Inflection>,
+}
+
+/// Whether a character is in a script which is written without spaces between words.
+#[inline]
+fn is_unspaced(c: char) -> bool {
+    matches!(c as u32,
+        0x0E00..=0x0EFF // Thai and Lao
+        | 0x1000..=0x109F // Myanmar
+        | 0x1100..=0x11FF // Hangul Jamo
+        | 0x1780..=0x17FF // Khmer
+        | 0x2E80..=0x2FDF // CJK radicals
+        | 0x3000..=0x31FF // CJK symbols, kana, Bopomofo and Hangul compatibility Jamo
+        | 0x3400..=0x4DBF // CJK Extension A
+        | 0x4E00..=0x9FFF // CJK Unified Ideographs
+        | 0xAC00..=0xD7AF // Hangul syllables
+        | 0xF900..=0xFAFF // CJK compatibility ideographs
+        | 0xFF66..=0xFFDC // halfwidth kana and Hangul
+        | 0x20000..=0x3134F // CJK Extension B to G
+    )
+}
+
+/// Whether a character is a part of a word in a space-delimited script.
+#[inline]
+fn is_spaced_word_char(c: char) -> bool {
+    (c.is_alphanumeric() || c == '_') && !is_unspaced(c)
+}
+
+/// Whether a match at the range is delimited by word boundaries.
+#[inline]
+fn is_at_word_boundaries(s: &str, range: Range<usize>) -> bool {
+    let edges = [
+        (s[..range.start].chars().next_back(), s[range.clone()].chars().next()),
+        (s[range.end..].chars().next(), s[range].chars().next_back()),
+    ];
+
+    edges.iter().all(|&(outside, inside)| match (outside, inside) {
+        (Some(outside), Some(inside)) => {
+            !(is_spaced_word_char(outside) && is_spaced_word_char(inside))
+        },
+        _ => true,
+    })
+}
+
+impl Dictionary {
+    /// Find non-overlapping left words in a plain text from left to right. The longest left word wins at each position.
+    pub(crate) fn find_left_occurrences(
+        &self,
+        s: &str,
+        options: &TransformOptions,
+    ) -> Vec<Occurrence> {
+        let matches = self.matcher.find_all(s);
+
+        let mut occurrences = Vec::new();
+
+        let mut last_end = 0;
+        let mut k = 0;
+
+        while k < matches.len() {
+            let i = matches[k].range.start;
+
+            // matches at the same position are adjacent
+            let group_end = matches[k..]
+                .iter()
+                .position(|m| m.range.start != i)
+                .map_or(matches.len(), |p| k + p);
+
+            let group = &matches[k..group_end];
+
+            k = group_end;
+
+            if i < last_end {
+                continue;
+            }
+
+            let mut found: Option<(usize, usize, Option<Inflection>)> = None;
+
+            for m in group {
+                if m.target != MatchTarget::Left || m.range.is_empty() {
+                    continue;
+                }
+
+                let left = &self.left[m.index];
+
+                let mut end = m.range.end;
+                let mut inflection = None;
+
+                if options.inflection {
+                    if let Some((suffix_len, suffix_inflection)) =
+                        inflection::find_suffix(s, i..end, left)
+                    {
+                        end += suffix_len;
+                        inflection = Some(suffix_inflection);
+                    }
+                }
+
+                let boundary = options.entry_boundaries.get(left).unwrap_or(&options.boundary);
+
+                if *boundary == BoundaryRule::Word && !is_at_word_boundaries(s, i..end) {
+                    continue;
+                }
+
+                if options.inflection
+                    && inflection.is_none()
+                    && inflection::is_in_compound(s, i..end)
+                {
+                    inflection = Some(Inflection::Compound);
+                }
+
+                if found
+                    .map_or(false, |(l, index, _)| l > end - i || l == end - i && index < m.index)
+                {
+                    continue;
+                }
+
+                found = Some((end - i, m.index, inflection));
+            }
+
+            if let Some((len, index, inflection)) = found {
+                occurrences.push(Occurrence {
+                    range: i..i + len,
+                    index,
+                    inflection,
+                });
+
+                last_end = i + len;
+            }
+        }
+
+        occurrences
+    }
+
+    /// Find non-overlapping left words in a text from left to right, using the given options. The longest left word wins at each position, and the case of ASCII letters is ignored. Markup is skipped.
+    pub fn scan<S: AsRef<str>>(&self, s: S, options: &TransformOptions) -> Vec<Occurrence> {
+        let s = s.as_ref();
+
+        let mut occurrences = Vec::new();
+
+        for (range, markup) in options.markup.segments(s) {
+            if markup {
+                continue;
+            }
+
+            occurrences.extend(
+                self.find_left_occurrences(&s[range.clone()], options).into_iter().map(
+                    |occurrence| Occurrence {
+                        range: occurrence.range.start + range.start
+                            ..occurrence.range.end + range.start,
+                        ..occurrence
+                    },
+                ),
+            );
+        }
+
+        occurrences
+    }
+
+    /// Replace the left words in a text with their current right words. Longer left words take precedence, and the case of ASCII letters is ignored.
+    #[inline]
+    pub fn transform<S: AsRef<str>>(&self, s: S) -> String {
+        self.transform_text(s.as_ref(), &TransformOptions::default())
+    }
+
+    /// Replace the left words in a text with their current right words, using the given options. See `transform`.
+    pub fn transform_with_options<S: AsRef<str>>(
+        &self,
+        s: S,
+        options: &TransformOptions,
+    ) -> String {
+        let s = s.as_ref();
+
+        options
+            .markup
+            .segments(s)
+            .into_iter()
+            .map(|(range, markup)| {
+                if markup {
+                    String::from(&s[range])
+                } else {
+                    self.transform_text(&s[range], options)
+                }
+            })
+            .collect()
+    }
+
+    fn transform_text(&self, s: &str, options: &TransformOptions) -> String {
+        let mut output = String::with_capacity(s.len());
+
+        let mut last = 0;
+
+        for Occurrence {
+            range,
+            index,
+            inflection,
+        } in self.find_left_occurrences(s, options)
+        {
+            let matched = &s[range.clone()];
+
+            let right = options.case.apply(matched, self.get_right(index).unwrap());
+
+            output.push_str(&s[last..range.start]);
+
+            match inflection {
+                Some(inflection) => {
+                    output.push_str(&inflection::inflect(&right, inflection, matched))
+                },
+                None => output.push_str(&right),
+            }
+
+            last = range.end;
+        }
+
+        output.push_str(&s[last..]);
+
+        output
+    }
+
+    /// Check whether every left word in a source text is translated with its current right word in a target text. Each entry is reported at most once.
+    pub fn check_terms<S: AsRef<str>, T: AsRef<str>>(
+        &self,
+        source: S,
+        target: T,
+    ) -> Vec<TermIssue> {
+        let target = target.as_ref();
+
+        // right words which are found in the target
+        let found: Vec<&str> = self
+            .matcher
+            .find_all(target)
+            .into_iter()
+            .filter(|m| matches!(m.target, MatchTarget::Right { .. }))
+            .map(|m| &target[m.range])
+            .collect();
+
+        let contains = |right: &str| found.iter().any(|f| f.eq_ignore_ascii_case(right));
+
+        let mut issues: Vec<TermIssue> = Vec::new();
+
+        for Occurrence {
+            index, ..
+        } in self.find_left_occurrences(source.as_ref(), &TransformOptions::default())
+        {
+            if issues.iter().any(|issue| issue.index == index) {
+                continue;
+            }
+
+            let right = &self.right[index];
+            let right_string = right.last().unwrap();
+
+            if contains(right_string) {
+                continue;
+            }
+
+            let kind = match right.iter().rev().skip(1).find(|s| contains(s)) {
+                Some(found) => TermIssueKind::Outdated {
+                    found: found.clone()
+                },
+                None => TermIssueKind::Missing,
+            };
+
+            issues.push(TermIssue {
+                index,
+                left_string: self.left[index].clone(),
+                right_string: right_string.clone(),
+                kind,
+            });
+        }
+
+        issues
+    }
+}
@@ -0,0 +1,231 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::{Dictionary, WriteError};
+
+/// A mutation of a dictionary which can be undone.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum OperationKind {
+    AddEdit,
+    Delete,
+    MergeEntries,
+    Import,
+    Merge,
+    ThreeWayMerge,
+}
+
+/// The states of an entry before and after an operation. A `None` means the entry does not exist.
+#[derive(Debug, Clone)]
+struct EntryEdit {
+    left:   String,
+    before: Option<Vec<String>>,
+    after:  Option<Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+struct Operation {
+    kind:  OperationKind,
+    edits: Vec<EntryEdit>,
+}
+
+/// The entries before an operation.
+#[derive(Debug)]
+pub(crate) struct Snapshot {
+    left:  Vec<String>,
+    right: Vec<Vec<String>>,
+}
+
+/// The operations which can be undone or redone.
+#[derive(Debug)]
+pub(crate) struct OperationLog {
+    undo:  VecDeque<Operation>,
+    redo:  Vec<Operation>,
+    depth: usize,
+}
+
+impl Default for OperationLog {
+    #[inline]
+    fn default() -> Self {
+        OperationLog {
+            undo: VecDeque::new(), redo: Vec::new(), depth: 100
+        }
+    }
+}
+
+impl OperationLog {
+    #[inline]
+    pub(crate) fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+impl Dictionary {
+    /// Set how many operations can be undone. The default depth is `100`, and `0` disables the operation log.
+    #[inline]
+    pub fn set_undo_depth(&mut self, depth: usize) {
+        let log = &mut self.operation_log;
+
+        log.depth = depth;
+
+        while log.undo.len() > depth {
+            log.undo.pop_front();
+        }
+
+        if depth == 0 {
+            log.redo.clear();
+        }
+    }
+
+    /// Get the kind of the operation which can be undone next.
+    #[inline]
+    pub fn can_undo(&self) -> Option<OperationKind> {
+        self.operation_log.undo.back().map(|operation| operation.kind)
+    }
+
+    /// Get the kind of the operation which can be redone next.
+    #[inline]
+    pub fn can_redo(&self) -> Option<OperationKind> {
+        self.operation_log.redo.last().map(|operation| operation.kind)
+    }
+
+    /// Copy the entries before an operation. Return `None` if the operation log is disabled.
+    #[inline]
+    pub(crate) fn snapshot(&self) -> Option<Snapshot> {
+        if self.operation_log.depth == 0 {
+            None
+        } else {
+            Some(Snapshot {
+                left: self.left.clone(), right: self.right.clone()
+            })
+        }
+    }
+
+    /// Record a change of a single entry as an operation, without taking a snapshot.
+    pub(crate) fn record_edit(
+        &mut self,
+        kind: OperationKind,
+        left: &str,
+        before: Option<Vec<String>>,
+        after: Option<Vec<String>>,
+    ) {
+        if self.operation_log.depth == 0 {
+            return;
+        }
+
+        self.push_operation(Operation {
+            kind,
+            edits: vec![EntryEdit {
+                left: String::from(left),
+                before,
+                after,
+            }],
+        });
+    }
+
+    /// Record the changed entries since a snapshot as an operation.
+    pub(crate) fn record(&mut self, kind: OperationKind, snapshot: Option<Snapshot>) {
+        let snapshot = match snapshot {
+            Some(snapshot) => snapshot,
+            None => return,
+        };
+
+        let mut current: HashMap<&str, &Vec<String>> =
+            self.left.iter().map(|s| s.as_str()).zip(self.right.iter()).collect();
+
+        let mut edits = Vec::new();
+
+        for (left, before) in snapshot.left.into_iter().zip(snapshot.right) {
+            match current.remove(left.as_str()) {
+                Some(after) if *after == before => (),
+                after => edits.push(EntryEdit {
+                    left,
+                    before: Some(before),
+                    after: after.cloned(),
+                }),
+            }
+        }
+
+        for (left, after) in current {
+            edits.push(EntryEdit {
+                left:   String::from(left),
+                before: None,
+                after:  Some(after.clone()),
+            });
+        }
+
+        if edits.is_empty() {
+            return;
+        }
+
+        self.push_operation(Operation {
+            kind,
+            edits,
+        });
+    }
+
+    /// Push an operation to the log. Operations which can be redone are dropped.
+    fn push_operation(&mut self, operation: Operation) {
+        let log = &mut self.operation_log;
+
+        log.redo.clear();
+        log.undo.push_back(operation);
+
+        if log.undo.len() > log.depth {
+            log.undo.pop_front();
+        }
+    }
+
+    /// Set entries to their states before or after an operation.
+    fn apply_edits(&mut self, edits: &[EntryEdit], after: bool) -> Result<(), WriteError> {
+        for edit in edits {
+            let state = if after { &edit.after } else { &edit.before };
+
+            match (self.left.iter().position(|left| *left == edit.left), state) {
+                (Some(index), Some(right)) => self.right[index] = right.clone(),
+                (Some(index), None) => {
+                    self.left.remove(index);
+                    self.right.remove(index);
+                },
+                (None, Some(right)) => {
+                    self.left.push(edit.left.clone());
+                    self.right.push(right.clone());
+                },
+                (None, None) => (),
+            }
+        }
+
+        self.rebuild_prefix_index();
+
+        self.write_data()
+    }
+
+    /// Undo the last operation, and write the dictionary file. Return the kind of the undone operation, or `None` if there is nothing to undo.
+    pub fn undo(&mut self) -> Result<Option<OperationKind>, WriteError> {
+        let operation = match self.operation_log.undo.pop_back() {
+            Some(operation) => operation,
+            None => return Ok(None),
+        };
+
+        let kind = operation.kind;
+        let result = self.apply_edits(&operation.edits, false);
+
+        self.operation_log.redo.push(operation);
+
+        result.map(|_| Some(kind))
+    }
+
+    /// Redo the last undone operation, and write the dictionary file. Return the kind of the redone operation, or `None` if there is nothing to redo.
+    pub fn redo(&mut self) -> Result<Option<OperationKind>, WriteError> {
+        let operation = match self.operation_log.redo.pop() {
+            Some(operation) => operation,
+            None => return Ok(None),
+        };
+
+        let kind = operation.kind;
+        let result = self.apply_edits(&operation.edits, true);
+
+        self.operation_log.undo.push_back(operation);
+
+        result.map(|_| Some(kind))
+    }
+}
@@ -0,0 +1,392 @@
+use std::{
+    collections::{HashMap, HashSet},
+    io::{self, Read, Write},
+};
+
+use crate::{
+    xml::{XmlDocument, XmlElement, XmlNode},
+    Dictionary, FormatError, Occurrence, TermIssue, TransformOptions,
+};
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum XliffVersion {
+    /// XLIFF 1.2, whose units are `<trans-unit>` elements.
+    V1_2,
+    /// XLIFF 2.0 or 2.1, whose units are `<unit>` elements containing `<segment>` elements.
+    V2_0,
+}
+
+/// A source segment with its target.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XliffSegment {
+    /// The `id` of the unit.
+    pub unit_id: String,
+    /// The index of the segment in the unit. It is always `0` for XLIFF 1.2.
+    pub segment: usize,
+    /// The line of the `<source>` element.
+    pub line:    usize,
+    /// The text of the source, without inline tags.
+    pub source:  String,
+    /// The text of the target, without inline tags.
+    pub target:  Option<String>,
+}
+
+/// An issue found in an XLIFF file.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct XliffIssue {
+    pub unit_id: String,
+    pub segment: usize,
+    /// The line of the `<source>` element.
+    pub line:    usize,
+    pub issue:   TermIssue,
+}
+
+/// An XLIFF 1.2 or 2.0 file.
+#[derive(Debug, Clone)]
+pub struct XliffFile {
+    document: XmlDocument,
+    version:  XliffVersion,
+}
+
+/// Make a name with the namespace prefix of another name.
+#[inline]
+fn prefixed(like: &str, local: &str) -> String {
+    match like.rfind(':') {
+        Some(i) => format!("{}{}", &like[..=i], local),
+        None => String::from(local),
+    }
+}
+
+/// Visit every element holding a `<source>` and an optional `<target>`, which is `<trans-unit>` for XLIFF 1.2 and `<segment>` for XLIFF 2.0.
+fn visit_segments<'a, F: FnMut(&'a str, usize, &'a XmlElement)>(
+    element: &'a XmlElement,
+    version: XliffVersion,
+    f: &mut F,
+) {
+    match version {
+        XliffVersion::V1_2 if element.is("trans-unit") => {
+            f(element.attribute("id").unwrap_or_default(), 0, element);
+        },
+        XliffVersion::V2_0 if element.is("unit") => {
+            let unit_id = element.attribute("id").unwrap_or_default();
+
+            for (i, segment) in element.elements().filter(|e| e.is("segment")).enumerate() {
+                f(unit_id, i, segment);
+            }
+        },
+        _ => {
+            for e in element.elements() {
+                visit_segments(e, version, f);
+            }
+        },
+    }
+}
+
+/// The mutable version of `visit_segments`.
+fn visit_segments_mut<F: FnMut(&str, usize, &mut XmlElement)>(
+    element: &mut XmlElement,
+    version: XliffVersion,
+    f: &mut F,
+) {
+    match version {
+        XliffVersion::V1_2 if element.is("trans-unit") => {
+            let unit_id = String::from(element.attribute("id").unwrap_or_default());
+
+            f(&unit_id, 0, element);
+        },
+        XliffVersion::V2_0 if element.is("unit") => {
+            let unit_id = String::from(element.attribute("id").unwrap_or_default());
+
+            for (i, segment) in element.elements_mut().filter(|e| e.is("segment")).enumerate() {
+                f(&unit_id, i, segment);
+            }
+        },
+        _ => {
+            for e in element.elements_mut() {
+                visit_segments_mut(e, version, f);
+            }
+        },
+    }
+}
+
+impl XliffFile {
+    /// Parse an XLIFF file. The version is decided by the `version` attribute of the root element.
+    pub fn parse<R: Read>(mut reader: R) -> Result<XliffFile, FormatError> {
+        let mut s = String::new();
+
+        reader.read_to_string(&mut s)?;
+
+        let document = XmlDocument::parse(&s)?;
+
+        let root = document.root();
+
+        let malformed = |reason: &str| FormatError::Malformed {
+            line:   root.line,
+            reason: String::from(reason),
+        };
+
+        if !root.is("xliff") {
+            return Err(malformed("the root element is not <xliff>"));
+        }
+
+        let version = match root.attribute("version") {
+            Some(v) if v.starts_with("1.") => XliffVersion::V1_2,
+            Some(v) if v.starts_with("2.") => XliffVersion::V2_0,
+            _ => return Err(malformed("unsupported XLIFF version")),
+        };
+
+        Ok(XliffFile {
+            document,
+            version,
+        })
+    }
+
+    #[inline]
+    pub fn version(&self) -> XliffVersion {
+        self.version
+    }
+
+    /// Get all segments in document order.
+    pub fn segments(&self) -> Vec<XliffSegment> {
+        let mut segments = Vec::new();
+
+        visit_segments(self.document.root(), self.version, &mut |unit_id, segment, e| {
+            if let Some(source) = e.child("source") {
+                segments.push(XliffSegment {
+                    unit_id: String::from(unit_id),
+                    segment,
+                    line: source.line,
+                    source: source.text(),
+                    target: e.child("target").map(|target| target.text()),
+                });
+            }
+        });
+
+        segments
+    }
+
+    /// Write this XLIFF file.
+    #[inline]
+    pub fn write_to<W: Write>(&self, mut writer: W) -> Result<(), io::Error> {
+        writer.write_all(self.document.to_xml_string().as_bytes())
+    }
+}
+
+/// Collect the IDs of the elements in every XLIFF 2.0 unit, by the unit ID.
+fn collect_unit_ids(element: &XmlElement, output: &mut HashMap<String, HashSet<String>>) {
+    if element.is("unit") {
+        let ids =
+            output.entry(String::from(element.attribute("id").unwrap_or_default())).or_default();
+
+        collect_ids(element, ids);
+    } else {
+        for e in element.elements() {
+            collect_unit_ids(e, output);
+        }
+    }
+}
+
+fn collect_ids(element: &XmlElement, ids: &mut HashSet<String>) {
+    for e in element.elements() {
+        if let Some(id) = e.attribute("id") {
+            ids.insert(String::from(id));
+        }
+
+        collect_ids(e, ids);
+    }
+}
+
+/// Marker IDs in a unit, which skip the IDs already used in it.
+#[derive(Default)]
+struct MarkerIds {
+    used:    HashSet<String>,
+    counter: usize,
+}
+
+impl MarkerIds {
+    fn next_id(&mut self) -> String {
+        loop {
+            self.counter += 1;
+
+            let id = format!("term{}", self.counter);
+
+            if self.used.insert(id.clone()) {
+                return id;
+            }
+        }
+    }
+}
+
+/// Wrap left words in text nodes with `<mrk>` elements. Existing `<mrk>` elements are skipped.
+fn annotate(
+    dictionary: &Dictionary,
+    element: &mut XmlElement,
+    version: XliffVersion,
+    ids: &mut MarkerIds,
+    count: &mut usize,
+) {
+    let mut children = Vec::with_capacity(element.children.len());
+
+    for node in element.children.drain(..) {
+        match node {
+            XmlNode::Element(mut e) => {
+                if !e.is("mrk") {
+                    annotate(dictionary, &mut e, version, ids, count);
+                }
+
+                children.push(XmlNode::Element(e));
+            },
+            XmlNode::Text {
+                text: t, ..
+            } => {
+                let mut last = 0;
+
+                for Occurrence {
+                    range,
+                    index,
+                    ..
+                } in dictionary.find_left_occurrences(&t, &TransformOptions::default())
+                {
+                    if range.start > last {
+                        children.push(XmlNode::text(&t[last..range.start]));
+                    }
+
+                    *count += 1;
+
+                    let right = dictionary.get_right(index).unwrap();
+
+                    let mrk = XmlElement::new(prefixed(&element.name, "mrk"));
+
+                    let mrk = match version {
+                        XliffVersion::V1_2 => {
+                            mrk.with_attribute("mtype", "term").with_attribute("comment", right)
+                        },
+                        XliffVersion::V2_0 => mrk
+                            .with_attribute("id", ids.next_id())
+                            .with_attribute("type", "term")
+                            .with_attribute("value", right),
+                    };
+
+                    children.push(XmlNode::Element(mrk.with_text(&t[range.clone()])));
+
+                    last = range.end;
+                }
+
+                if last < t.len() {
+                    children.push(XmlNode::text(&t[last..]));
+                }
+            },
+            node => children.push(node),
+        }
+    }
+
+    element.children = children;
+}
+
+impl Dictionary {
+    /// Check the terms of every translated segment in an XLIFF file. See `check_terms`.
+    pub fn check_xliff(&self, xliff: &XliffFile) -> Vec<XliffIssue> {
+        let mut issues = Vec::new();
+
+        for segment in xliff.segments() {
+            let target = match &segment.target {
+                Some(target) if !target.trim().is_empty() => target,
+                _ => continue,
+            };
+
+            for issue in self.check_terms(&segment.source, target) {
+                issues.push(XliffIssue {
+                    unit_id: segment.unit_id.clone(),
+                    segment: segment.segment,
+                    line: segment.line,
+                    issue,
+                });
+            }
+        }
+
+        issues
+    }
+
+    /// Fill empty or missing targets by transforming their sources, keeping inline tags. Only sources containing left words are used. The filled targets are marked as `needs-review-translation` in XLIFF 1.2 and their segments as `initial` in XLIFF 2.0. Return the count of filled targets.
+    pub fn pretranslate_xliff(&self, xliff: &mut XliffFile) -> usize {
+        let version = xliff.version;
+
+        let mut count = 0;
+
+        visit_segments_mut(xliff.document.root_mut(), version, &mut |_, _, e| {
+            let source = match e.child("source") {
+                Some(source) => source,
+                None => return,
+            };
+
+            if e.child("target").map_or(false, |target| !target.text().trim().is_empty())
+                || self
+                    .find_left_occurrences(&source.text(), &TransformOptions::default())
+                    .is_empty()
+            {
+                return;
+            }
+
+            let mut target = source.clone();
+
+            target.name = prefixed(&source.name, "target");
+            target.attributes.retain(|(k, _)| k == "xml:space");
+            target.map_text(&mut |t| self.transform(t));
+
+            match version {
+                XliffVersion::V1_2 => target.set_attribute("state", "needs-review-translation"),
+                XliffVersion::V2_0 => e.set_attribute("state", "initial"),
+            }
+
+            match e.child_mut("target") {
+                Some(old_target) => *old_target = target,
+                None => {
+                    let position = e
+                        .children
+                        .iter()
+                        .position(|node| matches!(node, XmlNode::Element(s) if s.is("source")))
+                        .unwrap();
+
+                    e.children.insert(position + 1, XmlNode::Element(target));
+                },
+            }
+
+            count += 1;
+        });
+
+        count
+    }
+
+    /// Wrap the left words in every source with term markers, which are `<mrk mtype="term">` in XLIFF 1.2 and `<mrk type="term">` in XLIFF 2.0. The current right word is put in the `comment` or `value` attribute. Return the count of markers.
+    pub fn annotate_xliff(&self, xliff: &mut XliffFile) -> usize {
+        let version = xliff.version;
+
+        let mut count = 0;
+
+        // marker IDs are unique in a unit
+        let mut unit_ids = HashMap::new();
+
+        if version == XliffVersion::V2_0 {
+            collect_unit_ids(xliff.document.root(), &mut unit_ids);
+        }
+
+        let mut last_unit_id = None;
+        let mut ids = MarkerIds::default();
+
+        visit_segments_mut(xliff.document.root_mut(), version, &mut |unit_id, _, e| {
+            if last_unit_id.as_deref() != Some(unit_id) {
+                last_unit_id = Some(String::from(unit_id));
+                ids = MarkerIds {
+                    used:    unit_ids.get(unit_id).cloned().unwrap_or_default(),
+                    counter: 0,
+                };
+            }
+
+            if let Some(source) = e.child_mut("source") {
+                annotate(self, source, version, &mut ids, &mut count);
+            }
+        });
+
+        count
+    }
+}
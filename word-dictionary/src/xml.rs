@@ -0,0 +1,611 @@
+//! A small XML reader and writer for the interchange formats. It keeps comments, processing instructions and CDATA sections, so a document can be written back nearly as it was read.
+
+use std::fmt::Write;
+
+use crate::FormatError;
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) enum XmlNode {
+    Element(XmlElement),
+    /// Unescaped text. The raw text is kept when escaping the text would not give it back, like with character references, so it can be written back verbatim while the text is unchanged.
+    Text {
+        text: String,
+        raw:  Option<String>,
+    },
+    CData(String),
+    /// Comments, processing instructions and DOCTYPE declarations, stored raw.
+    Other(String),
+    /// An entity reference which cannot be resolved, like `&nbsp;`, stored raw.
+    Reference(String),
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct XmlElement {
+    pub(crate) name:       String,
+    /// Unescaped attribute values.
+    pub(crate) attributes: Vec<(String, String)>,
+    pub(crate) children:   Vec<XmlNode>,
+    /// The line where the start tag is.
+    pub(crate) line:       usize,
+}
+
+impl XmlElement {
+    #[inline]
+    pub(crate) fn new<S: Into<String>>(name: S) -> XmlElement {
+        XmlElement {
+            name:       name.into(),
+            attributes: Vec::new(),
+            children:   Vec::new(),
+            line:       0,
+        }
+    }
+
+    /// The name without a namespace prefix.
+    #[inline]
+    pub(crate) fn local_name(&self) -> &str {
+        local_name(&self.name)
+    }
+
+    #[inline]
+    pub(crate) fn is(&self, local: &str) -> bool {
+        self.local_name() == local
+    }
+
+    /// Get an attribute by its full name, or by its local name if `name` has no prefix.
+    #[inline]
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(k, _)| k == name)
+            .or_else(|| self.attributes.iter().find(|(k, _)| local_name(k) == name))
+            .map(|(_, v)| v.as_str())
+    }
+
+    #[inline]
+    pub(crate) fn set_attribute<K: Into<String>, V: Into<String>>(&mut self, name: K, value: V) {
+        let name = name.into();
+        let value = value.into();
+
+        match self.attributes.iter_mut().find(|(k, _)| *k == name) {
+            Some((_, v)) => *v = value,
+            None => self.attributes.push((name, value)),
+        }
+    }
+
+    #[inline]
+    pub(crate) fn with_attribute<K: Into<String>, V: Into<String>>(
+        mut self,
+        name: K,
+        value: V,
+    ) -> XmlElement {
+        self.set_attribute(name, value);
+
+        self
+    }
+
+    #[inline]
+    pub(crate) fn with_text<S: Into<String>>(mut self, text: S) -> XmlElement {
+        self.children.push(XmlNode::text(text));
+
+        self
+    }
+
+    #[inline]
+    pub(crate) fn elements(&self) -> impl Iterator<Item = &XmlElement> {
+        self.children.iter().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    #[inline]
+    pub(crate) fn elements_mut(&mut self) -> impl Iterator<Item = &mut XmlElement> {
+        self.children.iter_mut().filter_map(|node| match node {
+            XmlNode::Element(element) => Some(element),
+            _ => None,
+        })
+    }
+
+    /// Find the first child element by its local name.
+    #[inline]
+    pub(crate) fn child(&self, local: &str) -> Option<&XmlElement> {
+        self.elements().find(|e| e.is(local))
+    }
+
+    #[inline]
+    pub(crate) fn child_mut(&mut self, local: &str) -> Option<&mut XmlElement> {
+        self.elements_mut().find(|e| e.is(local))
+    }
+
+    /// Collect all descendant elements (including itself) with a local name, in document order.
+    pub(crate) fn descendants<'a>(&'a self, local: &str, output: &mut Vec<&'a XmlElement>) {
+        if self.is(local) {
+            output.push(self);
+        }
+
+        for e in self.elements() {
+            e.descendants(local, output);
+        }
+    }
+
+    /// Concatenate all descendant text.
+    pub(crate) fn text(&self) -> String {
+        let mut s = String::new();
+
+        self.collect_text(&mut s);
+
+        s
+    }
+
+    fn collect_text(&self, s: &mut String) {
+        for node in self.children.iter() {
+            match node {
+                XmlNode::Element(e) => e.collect_text(s),
+                XmlNode::Text {
+                    text: t, ..
+                }
+                | XmlNode::CData(t)
+                | XmlNode::Reference(t) => s.push_str(t),
+                XmlNode::Other(_) => (),
+            }
+        }
+    }
+
+    /// Apply a function to every text node in the element, including the ones in descendants.
+    pub(crate) fn map_text<F: FnMut(&str) -> String>(&mut self, f: &mut F) {
+        for node in self.children.iter_mut() {
+            match node {
+                XmlNode::Element(e) => e.map_text(f),
+                XmlNode::Text {
+                    text: t, ..
+                }
+                | XmlNode::CData(t) => *t = f(t),
+                XmlNode::Other(_) | XmlNode::Reference(_) => (),
+            }
+        }
+    }
+
+    fn write_to(&self, s: &mut String) {
+        s.push('<');
+        s.push_str(&self.name);
+
+        for (k, v) in self.attributes.iter() {
+            write!(s, " {}=\"{}\"", k, escape(v, true)).unwrap();
+        }
+
+        if self.children.is_empty() {
+            s.push_str("/>");
+        } else {
+            s.push('>');
+
+            for node in self.children.iter() {
+                node.write_to(s);
+            }
+
+            write!(s, "</{}>", self.name).unwrap();
+        }
+    }
+}
+
+impl XmlNode {
+    #[inline]
+    pub(crate) fn text<S: Into<String>>(text: S) -> XmlNode {
+        XmlNode::Text {
+            text: text.into(), raw: None
+        }
+    }
+
+    fn write_to(&self, s: &mut String) {
+        match self {
+            XmlNode::Element(e) => e.write_to(s),
+            XmlNode::Text {
+                text,
+                raw: Some(raw),
+            } if unescape(raw) == *text => s.push_str(raw),
+            XmlNode::Text {
+                text, ..
+            } => s.push_str(&escape(text, false)),
+            XmlNode::CData(t) => write!(s, "<![CDATA[{}]]>", t).unwrap(),
+            XmlNode::Other(t) | XmlNode::Reference(t) => s.push_str(t),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub(crate) struct XmlDocument {
+    /// Top-level nodes. Exactly one of them is an element.
+    pub(crate) nodes: Vec<XmlNode>,
+}
+
+impl XmlDocument {
+    #[inline]
+    pub(crate) fn root(&self) -> &XmlElement {
+        self.nodes
+            .iter()
+            .find_map(|node| match node {
+                XmlNode::Element(e) => Some(e),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    #[inline]
+    pub(crate) fn root_mut(&mut self) -> &mut XmlElement {
+        self.nodes
+            .iter_mut()
+            .find_map(|node| match node {
+                XmlNode::Element(e) => Some(e),
+                _ => None,
+            })
+            .unwrap()
+    }
+
+    pub(crate) fn to_xml_string(&self) -> String {
+        let mut s = String::new();
+
+        for node in self.nodes.iter() {
+            node.write_to(&mut s);
+        }
+
+        s
+    }
+
+    /// Parse a document. A UTF-8 BOM is ignored.
+    pub(crate) fn parse(s: &str) -> Result<XmlDocument, FormatError> {
+        let s = s.strip_prefix('\u{FEFF}').unwrap_or(s);
+
+        let mut parser = Parser {
+            s,
+            pos: 0,
+            line: 1,
+        };
+
+        // the bottom of the stack holds the top-level nodes
+        let mut stack: Vec<XmlElement> = vec![XmlElement::new("")];
+
+        while parser.pos < s.len() {
+            let rest = &s[parser.pos..];
+
+            if rest.starts_with("<!--") {
+                let raw = parser.take_until("-->", "a comment is not closed")?;
+                stack.last_mut().unwrap().children.push(XmlNode::Other(String::from(raw)));
+            } else if rest.starts_with("<![CDATA[") {
+                let raw = parser.take_until("]]>", "a CDATA section is not closed")?;
+                stack
+                    .last_mut()
+                    .unwrap()
+                    .children
+                    .push(XmlNode::CData(String::from(&raw[9..raw.len() - 3])));
+            } else if rest.starts_with("<?") {
+                let raw = parser.take_until("?>", "a processing instruction is not closed")?;
+                stack.last_mut().unwrap().children.push(XmlNode::Other(String::from(raw)));
+            } else if rest.starts_with("<!") {
+                let raw = parser.take_declaration()?;
+                stack.last_mut().unwrap().children.push(XmlNode::Other(String::from(raw)));
+            } else if rest.starts_with("</") {
+                let line = parser.line;
+                let raw = parser.take_until(">", "an end tag is not closed")?;
+                let name = raw[2..raw.len() - 1].trim();
+
+                if stack.len() == 1 || stack.last().unwrap().name != name {
+                    return Err(FormatError::Malformed {
+                        line,
+                        reason: format!("unexpected end tag {:?}", name),
+                    });
+                }
+
+                let element = stack.pop().unwrap();
+                stack.last_mut().unwrap().children.push(XmlNode::Element(element));
+            } else if rest.starts_with('<') {
+                let (element, self_closing) = parser.take_start_tag()?;
+
+                if self_closing {
+                    stack.last_mut().unwrap().children.push(XmlNode::Element(element));
+                } else {
+                    stack.push(element);
+                }
+            } else {
+                let end = rest.find('<').unwrap_or(rest.len());
+                let raw = &rest[..end];
+
+                parser.advance(end);
+
+                push_text(&mut stack.last_mut().unwrap().children, raw);
+            }
+        }
+
+        if stack.len() > 1 {
+            let element = stack.pop().unwrap();
+
+            return Err(FormatError::Malformed {
+                line:   element.line,
+                reason: format!("the element {:?} is not closed", element.name),
+            });
+        }
+
+        let nodes = stack.pop().unwrap().children;
+
+        let element_count = nodes.iter().filter(|node| matches!(node, XmlNode::Element(_))).count();
+
+        if element_count != 1 {
+            return Err(FormatError::Malformed {
+                line:   parser.line,
+                reason: String::from("expected exactly one root element"),
+            });
+        }
+
+        Ok(XmlDocument {
+            nodes,
+        })
+    }
+}
+
+struct Parser<'a> {
+    s:    &'a str,
+    pos:  usize,
+    line: usize,
+}
+
+impl<'a> Parser<'a> {
+    #[inline]
+    fn advance(&mut self, len: usize) {
+        self.line += self.s[self.pos..self.pos + len].matches('\n').count();
+        self.pos += len;
+    }
+
+    /// Take the text from the current position to the end of `pattern`.
+    fn take_until(&mut self, pattern: &str, reason: &str) -> Result<&'a str, FormatError> {
+        let rest = &self.s[self.pos..];
+
+        match rest[1..].find(pattern) {
+            Some(i) => {
+                let len = i + 1 + pattern.len();
+
+                self.advance(len);
+
+                Ok(&rest[..len])
+            },
+            None => Err(FormatError::Malformed {
+                line: self.line, reason: String::from(reason)
+            }),
+        }
+    }
+
+    /// Take a `<!DOCTYPE ...>` declaration, which may contain an internal subset in brackets.
+    fn take_declaration(&mut self) -> Result<&'a str, FormatError> {
+        let rest = &self.s[self.pos..];
+
+        let mut depth = 0usize;
+
+        for (i, c) in rest.char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth = depth.saturating_sub(1),
+                '>' if depth == 0 => {
+                    self.advance(i + 1);
+
+                    return Ok(&rest[..=i]);
+                },
+                _ => (),
+            }
+        }
+
+        Err(FormatError::Malformed {
+            line:   self.line,
+            reason: String::from("a declaration is not closed"),
+        })
+    }
+
+    fn take_start_tag(&mut self) -> Result<(XmlElement, bool), FormatError> {
+        let line = self.line;
+        let rest = &self.s[self.pos..];
+
+        let malformed = |reason: &str| FormatError::Malformed {
+            line,
+            reason: String::from(reason),
+        };
+
+        // find the end of the tag, skipping quoted attribute values
+        let mut quote = None;
+        let mut end = None;
+
+        for (i, c) in rest.char_indices().skip(1) {
+            match quote {
+                Some(q) if c == q => quote = None,
+                Some(_) => (),
+                None => match c {
+                    '"' | '\'' => quote = Some(c),
+                    '>' => {
+                        end = Some(i);
+                        break;
+                    },
+                    '<' => return Err(malformed("a start tag is not closed")),
+                    _ => (),
+                },
+            }
+        }
+
+        let end = end.ok_or_else(|| malformed("a start tag is not closed"))?;
+
+        let mut inner = &rest[1..end];
+
+        let self_closing = inner.ends_with('/');
+
+        if self_closing {
+            inner = &inner[..inner.len() - 1];
+        }
+
+        let name_end = inner.find(|c: char| c.is_whitespace()).unwrap_or(inner.len());
+        let name = &inner[..name_end];
+
+        if name.is_empty() {
+            return Err(malformed("an element has no name"));
+        }
+
+        let mut element = XmlElement::new(name);
+        element.line = line;
+
+        let mut attributes = inner[name_end..].trim_start();
+
+        while !attributes.is_empty() {
+            let eq = attributes.find('=').ok_or_else(|| malformed("an attribute has no value"))?;
+            let key = attributes[..eq].trim();
+            let value = attributes[eq + 1..].trim_start();
+
+            let q = match value.chars().next() {
+                Some(q @ ('"' | '\'')) => q,
+                _ => return Err(malformed("an attribute value is not quoted")),
+            };
+
+            let value_end =
+                value[1..].find(q).ok_or_else(|| malformed("an attribute value is not closed"))?;
+
+            element.attributes.push((String::from(key), unescape(&value[1..=value_end])));
+
+            attributes = value[value_end + 2..].trim_start();
+        }
+
+        self.advance(end + 1);
+
+        Ok((element, self_closing))
+    }
+}
+
+#[inline]
+pub(crate) fn local_name(name: &str) -> &str {
+    match name.rfind(':') {
+        Some(i) => &name[i + 1..],
+        None => name,
+    }
+}
+
+/// Decode a predefined entity or a character reference at the start of `s`. Return the character and the length of the reference.
+fn decode_reference(s: &str) -> Option<(char, usize)> {
+    let end = s.find(';')?;
+    let entity = &s[1..end];
+
+    let c = match entity {
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        _ => {
+            if let Some(hex) = entity.strip_prefix("#x").or_else(|| entity.strip_prefix("#X")) {
+                u32::from_str_radix(hex, 16).ok().and_then(char::from_u32)
+            } else if let Some(dec) = entity.strip_prefix('#') {
+                dec.parse().ok().and_then(char::from_u32)
+            } else {
+                None
+            }
+        },
+    };
+
+    c.map(|c| (c, end + 1))
+}
+
+/// Get the length of an entity reference like `&nbsp;` at the start of `s`.
+fn entity_reference_len(s: &str) -> Option<usize> {
+    let end = s.find(';')?;
+    let name = &s[1..end];
+
+    if name.is_empty()
+        || !name.chars().all(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | '.' | ':'))
+    {
+        return None;
+    }
+
+    Some(end + 1)
+}
+
+/// Unescape raw text into text nodes. Entity references which cannot be resolved are kept as they are in their own nodes, so they can be written back verbatim.
+fn push_text(children: &mut Vec<XmlNode>, raw: &str) {
+    let mut start = 0;
+    let mut i = 0;
+
+    while let Some(p) = raw[i..].find('&') {
+        i += p;
+
+        let rest = &raw[i..];
+
+        match entity_reference_len(rest) {
+            Some(len) if decode_reference(rest).is_none() => {
+                push_raw_text(children, &raw[start..i]);
+
+                children.push(XmlNode::Reference(String::from(&rest[..len])));
+
+                i += len;
+                start = i;
+            },
+            _ => i += 1,
+        }
+    }
+
+    push_raw_text(children, &raw[start..]);
+}
+
+#[inline]
+fn push_raw_text(children: &mut Vec<XmlNode>, raw: &str) {
+    if raw.is_empty() {
+        return;
+    }
+
+    let text = unescape(raw);
+
+    let raw = if escape(&text, false) == raw { None } else { Some(String::from(raw)) };
+
+    children.push(XmlNode::Text {
+        text,
+        raw,
+    });
+}
+
+/// Decode the predefined entities and character references. Unknown entities are left as they are.
+pub(crate) fn unescape(s: &str) -> String {
+    if !s.contains('&') {
+        return String::from(s);
+    }
+
+    let mut output = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(i) = rest.find('&') {
+        output.push_str(&rest[..i]);
+        rest = &rest[i..];
+
+        match decode_reference(rest) {
+            Some((c, len)) => {
+                output.push(c);
+                rest = &rest[len..];
+            },
+            None => {
+                output.push('&');
+                rest = &rest[1..];
+            },
+        }
+    }
+
+    output.push_str(rest);
+
+    output
+}
+
+/// Escape text or an attribute value. Whitespace characters other than spaces are escaped in attribute values, because they would be normalized to spaces when read.
+pub(crate) fn escape(s: &str, attribute: bool) -> String {
+    let mut output = String::with_capacity(s.len());
+
+    for c in s.chars() {
+        match c {
+            '<' => output.push_str("&lt;"),
+            '>' => output.push_str("&gt;"),
+            '&' => output.push_str("&amp;"),
+            '"' if attribute => output.push_str("&quot;"),
+            '\n' if attribute => output.push_str("&#10;"),
+            '\r' if attribute => output.push_str("&#13;"),
+            '\t' if attribute => output.push_str("&#9;"),
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
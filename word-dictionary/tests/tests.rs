@@ -1,4 +1,4 @@
-use std::{fs, path::Path};
+use std::{collections::HashMap, fs, path::Path};
 
 use word_dictionary::*;
 
@@ -104,3 +104,1985 @@ Abhai = 阿拜";
     assert!(dictionary.add_edit("Abez", "阿貝茲").is_err());
     assert!(!dictionary.delete(2).unwrap());
 }
+
+#[test]
+fn table_import_export() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("table_import_export.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let mut csv = Vec::new();
+
+    dictionary.export_table(&mut csv, TableFormat::Csv, &HashMap::new()).unwrap();
+
+    assert_eq!(
+        "left,right,history\r\nAlduin,奥杜因,\"阿爾杜因 --> 奥杜因\"\r\nAldun,奧爾敦,奧爾敦\r\n",
+        String::from_utf8(csv).unwrap()
+    );
+
+    let tsv = "left\tright\thistory\tmetadata
+Althasol\t阿爾瑟索\t\t\"dragon, \"\"Skyrim\"\"\"
+Aldun\t奥尔敦\t\t
+Alduin\t奥杜因\t\t
+\"A=B\"\tX\t\t
+Abez\t\t\"阿別茲 --> 阿貝茲\"\tnote";
+
+    let records = read_table(tsv.as_bytes(), TableFormat::Tsv).unwrap();
+
+    assert_eq!(5, records.len());
+    assert_eq!(2, records[0].line);
+    assert_eq!(Some("dragon, \"Skyrim\""), records[0].metadata.as_deref());
+    assert_eq!(vec!["阿別茲", "阿貝茲"], records[4].entry.right);
+
+    // only the full header row is skipped
+    let records = read_table("left,right\r\nleft,右".as_bytes(), TableFormat::Csv).unwrap();
+
+    assert_eq!(2, records.len());
+    assert_eq!("left", records[0].entry.left);
+
+    let TableImportReport {
+        report,
+        metadata,
+    } = dictionary
+        .import_table(tsv.as_bytes(), TableFormat::Tsv, ImportMode::KeepExisting)
+        .unwrap();
+
+    assert_eq!(2, metadata.len());
+    assert_eq!("note", metadata["Abez"]);
+
+    assert_eq!(2, report.added);
+    assert_eq!(0, report.updated);
+    assert_eq!(3, report.conflicts.len());
+    assert_eq!(3, report.conflicts[0].position);
+    assert_eq!(
+        ConflictReason::Changed {
+            current_right_string: String::from("奧爾敦"),
+            new_right_string:     String::from("奥尔敦"),
+        },
+        report.conflicts[0].reason
+    );
+    assert_eq!(ConflictReason::Duplicated, report.conflicts[1].reason);
+    assert_eq!(ConflictReason::BadLeftString, report.conflicts[2].reason);
+
+    let report = dictionary
+        .import_table(tsv.as_bytes(), TableFormat::Tsv, ImportMode::AddEdit)
+        .unwrap()
+        .report;
+
+    assert_eq!(0, report.added);
+    assert_eq!(1, report.updated);
+
+    let dictionary_data = "Abez = 阿別茲 --> 阿貝茲
+Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦 --> 奥尔敦
+Althasol = 阿爾瑟索";
+
+    assert_eq!(dictionary_data, fs::read_to_string(&dictionary_path).unwrap());
+
+    let mut csv = Vec::new();
+
+    dictionary.export_table(&mut csv, TableFormat::Csv, &metadata).unwrap();
+
+    let csv = String::from_utf8(csv).unwrap();
+
+    assert!(csv
+        .starts_with("left,right,history,metadata\r\nAbez,阿貝茲,\"阿別茲 --> 阿貝茲\",note\r\n"));
+    assert!(csv.ends_with("Althasol,阿爾瑟索,阿爾瑟索,\"dragon, \"\"Skyrim\"\"\"\r\n"));
+
+    assert!(read_table("Abez,\"阿別茲".as_bytes(), TableFormat::Csv).is_err());
+
+    // line breaks cannot be written to the dictionary file
+    let report = dictionary
+        .import_table(
+            "\"Al\nduin\",阿爾杜因\r\nAzura,\"阿茲\r\n拉\"".as_bytes(),
+            TableFormat::Csv,
+            ImportMode::AddEdit,
+        )
+        .unwrap()
+        .report;
+
+    assert_eq!(0, report.added);
+    assert_eq!(ConflictReason::BadLeftString, report.conflicts[0].reason);
+    assert!(matches!(report.conflicts[1].reason, ConflictReason::BadRightString { .. }));
+    assert!(matches!(dictionary.add_edit("Al\nduin", "阿爾杜因"), Err(WriteError::BadLeftString)));
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(4, dictionary.count());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn json_import_export() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("json_import_export.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let mut json = Vec::new();
+
+    dictionary.export_json(&mut json).unwrap();
+
+    let entries = read_json(json.as_slice()).unwrap();
+
+    assert_eq!(2, entries.len());
+    assert_eq!(
+        Entry::new("Alduin", vec![String::from("阿爾杜因"), String::from("奥杜因")]),
+        entries[0]
+    );
+
+    let json = r#"[
+    {"left": "Althasol", "right": ["阿爾瑟索"]},
+    {"left": "Aldun", "right": ["奥尔敦"]},
+    {"left": "Abez", "right": []}
+]"#;
+
+    let report = dictionary.import_json(json.as_bytes(), ImportMode::AddEdit).unwrap();
+
+    assert_eq!(1, report.added);
+    assert_eq!(1, report.updated);
+    assert_eq!(1, report.conflicts.len());
+    assert_eq!(2, report.conflicts[0].position);
+
+    assert_eq!(
+        Some("奥尔敦"),
+        dictionary.get_right(dictionary.find_left_strictly("Aldun", 0).unwrap())
+    );
+
+    match dictionary.import_json("[{\"left\": \"Abez\"}".as_bytes(), ImportMode::AddEdit) {
+        Err(ImportError::FormatError(FormatError::Malformed {
+            line, ..
+        })) => assert_eq!(1, line),
+        _ => panic!(),
+    }
+}
+
+#[test]
+fn tbx_import_export() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("tbx_import_export.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let mut tbx = Vec::new();
+
+    dictionary.export_tbx(&mut tbx, "en", "zh-TW").unwrap();
+
+    let tbx = String::from_utf8(tbx).unwrap();
+
+    assert!(tbx.contains(
+        "<term>阿爾杜因</term>\n            <termNote \
+         type=\"administrativeStatus\">deprecatedTerm-admn-sts</termNote>"
+    ));
+
+    let entries = read_tbx(tbx.as_bytes(), "en", "zh-TW").unwrap();
+
+    assert_eq!(2, entries.len());
+    assert_eq!(
+        Entry::new("Alduin", vec![String::from("阿爾杜因"), String::from("奥杜因")]),
+        entries[0]
+    );
+
+    let tbx = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tbx type="TBX-Basic" style="dca" xml:lang="en" xmlns="urn:iso:std:iso:30042:ed-2">
+  <text>
+    <body>
+      <conceptEntry id="c1">
+        <langSec xml:lang="zh-Hant">
+          <termSec>
+            <term>奧爾敦</term>
+            <termNote type="administrativeStatus">supersededTerm</termNote>
+          </termSec>
+          <termSec>
+            <term>奥尔敦</term>
+            <termNote type="administrativeStatus">preferredTerm</termNote>
+          </termSec>
+        </langSec>
+        <langSec xml:lang="en-US">
+          <termSec><term>Aldun</term></termSec>
+        </langSec>
+      </conceptEntry>
+      <conceptEntry id="c2">
+        <langSec xml:lang="en"><termSec><term>Althasol &amp; Co</term></termSec></langSec>
+        <langSec xml:lang="zh-Hant"><termSec><term>阿爾瑟索</term></termSec></langSec>
+      </conceptEntry>
+    </body>
+  </text>
+</tbx>"#;
+
+    let entries = read_tbx(tbx.as_bytes(), "en", "zh-Hant").unwrap();
+
+    assert_eq!(
+        Entry::new("Aldun", vec![String::from("奧爾敦"), String::from("奥尔敦")]),
+        entries[0]
+    );
+    assert_eq!("Althasol & Co", entries[1].left);
+
+    let report =
+        dictionary.import_tbx(tbx.as_bytes(), "en", "zh-Hant", ImportMode::AddEdit).unwrap();
+
+    assert_eq!(1, report.added);
+    assert_eq!(1, report.updated);
+    assert!(report.conflicts.is_empty());
+
+    assert!(read_tbx("<martif><text></martif>".as_bytes(), "en", "zh-TW").is_err());
+}
+
+#[test]
+fn transform_and_check_terms() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("transform_and_check_terms.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦
+Dragon = 龍";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!("龍神奥杜因和奧爾敦", dictionary.transform("DRAGON神Alduin和aldun"));
+
+    let issues = dictionary.check_terms("Alduin, the dragon of Aldun", "阿爾杜因，奧爾敦");
+
+    assert_eq!(2, issues.len());
+    assert_eq!("Alduin", issues[0].left_string);
+    assert_eq!("奥杜因", issues[0].right_string);
+    assert_eq!(
+        TermIssueKind::Outdated {
+            found: String::from("阿爾杜因")
+        },
+        issues[0].kind
+    );
+    assert_eq!("Dragon", issues[1].left_string);
+    assert_eq!(TermIssueKind::Missing, issues[1].kind);
+}
+
+#[test]
+fn po_check_and_prefill() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("po_check_and_prefill.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let po = r#"msgid ""
+msgstr ""
+"Language: zh_TW\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: quest.c:10
+msgid ""
+"Alduin returns"
+msgstr "阿爾杜因回來了"
+
+#: quest.c:20
+#, c-format
+msgid "Go to Aldun"
+msgstr ""
+
+msgctxt "menu"
+msgid "Quit"
+msgstr ""
+
+msgid "One \"Alduin\""
+msgid_plural "%d Alduins"
+msgstr[0] ""
+msgstr[1] ""
+
+msgid "Quit game"
+"#;
+
+    let mut po = PoFile::parse(po.as_bytes()).unwrap();
+
+    assert_eq!(6, po.entries.len());
+    assert!(po.entries[0].is_header());
+    assert_eq!(
+        "Language: zh_TW\nContent-Type: text/plain; charset=UTF-8\n",
+        po.entries[0].msgstr[0]
+    );
+
+    let issues = dictionary.check_po(&po);
+
+    assert_eq!(1, issues.len());
+    assert_eq!(7, issues[0].line);
+    assert!(po.entries[5].msgstr.is_empty());
+    assert_eq!(
+        TermIssueKind::Outdated {
+            found: String::from("阿爾杜因")
+        },
+        issues[0].issue.kind
+    );
+
+    assert_eq!(2, dictionary.prefill_po(&mut po));
+
+    let mut output = Vec::new();
+
+    po.write_to(&mut output).unwrap();
+
+    assert_eq!(
+        r#"msgid ""
+msgstr ""
+"Language: zh_TW\n"
+"Content-Type: text/plain; charset=UTF-8\n"
+
+#: quest.c:10
+msgid ""
+"Alduin returns"
+msgstr "阿爾杜因回來了"
+
+#: quest.c:20
+#, c-format, fuzzy
+msgid "Go to Aldun"
+msgstr "Go to 奧爾敦"
+
+msgctxt "menu"
+msgid "Quit"
+msgstr ""
+
+#, fuzzy
+msgid "One \"Alduin\""
+msgid_plural "%d Alduins"
+msgstr[0] "One \"奥杜因\""
+msgstr[1] "%d 奥杜因s"
+
+msgid "Quit game"
+"#,
+        String::from_utf8(output).unwrap()
+    );
+
+    assert!(PoFile::parse("msgid \"Alduin".as_bytes()).is_err());
+}
+
+#[test]
+fn xliff_check_and_pretranslate() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("xliff_check_and_pretranslate.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let xliff = r#"<?xml version="1.0" encoding="UTF-8"?>
+<xliff version="1.2" xmlns="urn:oasis:names:tc:xliff:document:1.2">
+  <file source-language="en" target-language="zh-TW" datatype="plaintext" original="quest">
+    <body>
+      <trans-unit id="1">
+        <source>Alduin returns</source>
+        <target>阿爾杜因回來了</target>
+      </trans-unit>
+      <trans-unit id="2">
+        <source>Go to <g id="b">Aldun</g> &amp; rest</source>
+      </trans-unit>
+      <trans-unit id="3" resname="quit&#10;menu">
+        <source>Quit&nbsp;now</source>
+        <target></target>
+      </trans-unit>
+    </body>
+  </file>
+</xliff>"#;
+
+    let mut xliff = XliffFile::parse(xliff.as_bytes()).unwrap();
+
+    assert_eq!(XliffVersion::V1_2, xliff.version());
+    assert_eq!(3, xliff.segments().len());
+    assert_eq!("Go to Aldun & rest", xliff.segments()[1].source);
+
+    let issues = dictionary.check_xliff(&xliff);
+
+    assert_eq!(1, issues.len());
+    assert_eq!("1", issues[0].unit_id);
+    assert_eq!(6, issues[0].line);
+    assert_eq!(
+        TermIssueKind::Outdated {
+            found: String::from("阿爾杜因")
+        },
+        issues[0].issue.kind
+    );
+
+    assert_eq!(1, dictionary.pretranslate_xliff(&mut xliff));
+    assert_eq!(2, dictionary.annotate_xliff(&mut xliff));
+
+    let mut output = Vec::new();
+
+    xliff.write_to(&mut output).unwrap();
+
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output
+        .contains(r#"<source><mrk mtype="term" comment="奥杜因">Alduin</mrk> returns</source>"#));
+    assert!(output.contains(r#"<source>Go to <g id="b"><mrk mtype="term" comment="奧爾敦">Aldun</mrk></g> &amp; rest</source><target state="needs-review-translation">Go to <g id="b">奧爾敦</g> &amp; rest</target>"#));
+    assert!(output.contains(
+        "<trans-unit id=\"3\" resname=\"quit&#10;menu\">\n        \
+         <source>Quit&nbsp;now</source>\n        <target/>"
+    ));
+
+    let xliff = r#"<xliff version="2.0" srcLang="en" trgLang="zh-TW" xmlns="urn:oasis:names:tc:xliff:document:2.0">
+  <file id="f1">
+    <unit id="u1">
+      <segment><source>Alduin</source><target>奥杜因</target></segment>
+      <segment id="term1"><source>Aldun</source><target>奥尔敦</target></segment>
+    </unit>
+  </file>
+</xliff>"#;
+
+    let mut xliff = XliffFile::parse(xliff.as_bytes()).unwrap();
+
+    assert_eq!(XliffVersion::V2_0, xliff.version());
+
+    let issues = dictionary.check_xliff(&xliff);
+
+    assert_eq!(1, issues.len());
+    assert_eq!(1, issues[0].segment);
+    assert_eq!(TermIssueKind::Missing, issues[0].issue.kind);
+
+    assert_eq!(2, dictionary.annotate_xliff(&mut xliff));
+
+    let mut output = Vec::new();
+
+    xliff.write_to(&mut output).unwrap();
+
+    assert!(String::from_utf8(output)
+        .unwrap()
+        .contains(r#"<source><mrk id="term3" type="term" value="奧爾敦">Aldun</mrk></source>"#));
+
+    assert!(XliffFile::parse("<xliff version=\"3.0\"/>".as_bytes()).is_err());
+}
+
+#[test]
+fn string_table_transform_and_check() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("string_table_transform_and_check.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    for kind in [StringTableKind::Strings, StringTableKind::DlStrings, StringTableKind::IlStrings] {
+        let source = StringTable {
+            kind,
+            entries: vec![
+                StringTableEntry {
+                    id: 1, text: String::from("Alduin\u{2019}s Wall")
+                },
+                StringTableEntry {
+                    id: 7, text: String::from("Aldun")
+                },
+                StringTableEntry {
+                    id: 9, text: String::from("Aldun")
+                },
+            ],
+        };
+
+        let mut data = Vec::new();
+
+        source.write_to(&mut data, StringsEncoding::Windows1252).unwrap();
+
+        // three directory entries and two shared strings
+        assert_eq!(
+            8 + 3 * 8,
+            data.len() - u32::from_le_bytes([data[4], data[5], data[6], data[7]]) as usize
+        );
+        assert_eq!(0x92, data[8 + 3 * 8 + if kind == StringTableKind::Strings { 6 } else { 10 }]);
+
+        let source =
+            StringTable::read(data.as_slice(), kind, StringsEncoding::Windows1252).unwrap();
+
+        assert_eq!("Alduin\u{2019}s Wall", source.get(1).unwrap());
+        assert_eq!("Aldun", source.get(9).unwrap());
+
+        let mut target = source.clone();
+
+        assert_eq!(3, dictionary.transform_string_table(&mut target));
+        assert_eq!("奥杜因\u{2019}s Wall", target.get(1).unwrap());
+
+        assert!(target.write_to(Vec::new(), StringsEncoding::Windows1252).is_err());
+
+        let mut data = Vec::new();
+
+        target.write_to(&mut data, StringsEncoding::Utf8).unwrap();
+
+        let mut target = StringTable::read(data.as_slice(), kind, StringsEncoding::Utf8).unwrap();
+
+        target.entries[0].text = String::from("阿爾杜因之牆");
+
+        let issues = dictionary.check_string_table(&source, &target);
+
+        assert_eq!(1, issues.len());
+        assert_eq!(1, issues[0].id);
+        assert_eq!(
+            TermIssueKind::Outdated {
+                found: String::from("阿爾杜因")
+            },
+            issues[0].issue.kind
+        );
+
+        assert!(StringTable::read(&data[..data.len() - 1], kind, StringsEncoding::Utf8).is_err());
+    }
+
+    assert_eq!(Some(StringTableKind::DlStrings), StringTableKind::from_extension("DLSTRINGS"));
+}
+
+#[test]
+fn i18n_resources() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("i18n_resources.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let transform = |s: &str, format: ResourceFormat| {
+        let mut output = Vec::new();
+
+        let count = dictionary.transform_resource(s.as_bytes(), &mut output, format).unwrap();
+
+        (count, String::from_utf8(output).unwrap())
+    };
+
+    assert_eq!(
+        "奥杜因 {name} 奥杜因 %1$s <b>奧爾敦</b>",
+        dictionary.transform_message("Alduin {name} Alduin %1$s <b>Aldun</b>")
+    );
+
+    let json = r#"{
+  "quest": {
+    "title": "Alduin returns",
+    "steps": ["Go to Aldun", "Alduin"],
+    "Alduin": "Hi {Alduin}, \"Aldun\"\n"
+  }
+}"#;
+
+    assert_eq!(
+        (
+            4,
+            String::from(
+                r#"{
+  "quest": {
+    "title": "奥杜因 returns",
+    "steps": ["Go to 奧爾敦", "奥杜因"],
+    "Alduin": "Hi {Alduin}, \"奧爾敦\"\n"
+  }
+}"#
+            )
+        ),
+        transform(json, ResourceFormat::Json)
+    );
+
+    let messages = read_resource(json.as_bytes(), ResourceFormat::Json).unwrap();
+
+    assert_eq!(
+        vec!["quest.title", "quest.steps[0]", "quest.steps[1]", "quest.Alduin"],
+        messages.iter().map(|m| m.key.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!(4, messages[2].line);
+    assert!(read_resource(r#"{"a": [1}"#.as_bytes(), ResourceFormat::Json).is_err());
+
+    let yaml = "en:
+  # Alduin
+  quest:
+    title: Alduin returns # Alduin
+    quoted: 'Alduin''s wall'
+    steps:
+      - Go to Aldun
+      - name: Alduin
+    description: |
+      Alduin
+      and Aldun
+  other: \"Aldun: %{count} \\\"C:\\\\\\\"\\n\"
+";
+
+    assert_eq!(
+        (
+            6,
+            String::from(
+                "en:
+  # Alduin
+  quest:
+    title: 奥杜因 returns # Alduin
+    quoted: '奥杜因''s wall'
+    steps:
+      - Go to 奧爾敦
+      - name: 奥杜因
+    description: |
+      奥杜因
+      and 奧爾敦
+  other: \"奧爾敦: %{count} \\\"C:\\\\\\\"\\n\"
+"
+            )
+        ),
+        transform(yaml, ResourceFormat::Yaml)
+    );
+
+    let messages = read_resource(yaml.as_bytes(), ResourceFormat::Yaml).unwrap();
+
+    assert_eq!(
+        vec![
+            "en.quest.title",
+            "en.quest.quoted",
+            "en.quest.steps[0]",
+            "en.quest.steps[1].name",
+            "en.quest.description",
+            "en.other"
+        ],
+        messages.iter().map(|m| m.key.as_str()).collect::<Vec<_>>()
+    );
+    assert_eq!("Alduin and Aldun", messages[4].value);
+
+    let fluent = "# Alduin
+quest-title = Alduin returns to { -place }
+    .tooltip = Beware Alduin
+-place = Aldun
+kills = { $count ->
+    [one] Alduin was killed
+   *[other] { $count } Alduins were killed
+}
+";
+
+    assert_eq!(
+        (
+            4,
+            String::from(
+                "# Alduin
+quest-title = 奥杜因 returns to { -place }
+    .tooltip = Beware 奥杜因
+-place = 奧爾敦
+kills = { $count ->
+    [one] 奥杜因 was killed
+   *[other] { $count } 奥杜因s were killed
+}
+"
+            )
+        ),
+        transform(fluent, ResourceFormat::Fluent)
+    );
+
+    let messages = read_resource(fluent.as_bytes(), ResourceFormat::Fluent).unwrap();
+
+    assert_eq!(
+        vec!["quest-title", "quest-title.tooltip", "-place", "kills"],
+        messages.iter().map(|m| m.key.as_str()).collect::<Vec<_>>()
+    );
+
+    assert!(read_resource("a = { $b".as_bytes(), ResourceFormat::Fluent).is_err());
+
+    let android = r#"<?xml version="1.0" encoding="utf-8"?>
+<resources xmlns:xliff="urn:oasis:names:tc:xliff:document:1.2">
+    <string name="app_name" translatable="false">Alduin</string>
+    <string name="title">Alduin &amp; <xliff:g id="place">Aldun</xliff:g></string>
+    <string-array name="steps">
+        <item>Go to Aldun</item>
+        <item>@string/title</item>
+    </string-array>
+    <plurals name="kills">
+        <item quantity="one">%d Alduin</item>
+    </plurals>
+    <string name="wall">"Alduin\'s  wall"</string>
+    <string name="hill">Paarthurnax&#8217;s hill &gt; \"Aldun\"</string>
+    <string name="peak">Paarthurnax&#8217;s peak &gt; \@home</string>
+</resources>"#;
+
+    let (count, output) = transform(android, ResourceFormat::AndroidStrings);
+
+    assert_eq!(5, count);
+    assert!(output.contains(r#"<string name="app_name" translatable="false">Alduin</string>"#));
+    assert!(output.contains(
+        r#"<string name="title">奥杜因 &amp; <xliff:g id="place">Aldun</xliff:g></string>"#
+    ));
+    assert!(output.contains("<item>Go to 奧爾敦</item>"));
+    assert!(output.contains(r#"<item quantity="one">%d 奥杜因</item>"#));
+    assert!(output.contains(r#"<string name="wall">"奥杜因\'s  wall"</string>"#));
+    assert!(output.contains(r#"<string name="hill">Paarthurnax’s hill &gt; \"奧爾敦\"</string>"#));
+    assert!(output.contains(r#"<string name="peak">Paarthurnax&#8217;s peak &gt; \@home</string>"#));
+
+    let translated = output.replace("%d 奥杜因", "%d 阿爾杜因");
+
+    let issues = dictionary
+        .check_resource(android.as_bytes(), translated.as_bytes(), ResourceFormat::AndroidStrings)
+        .unwrap();
+
+    assert_eq!(1, issues.len());
+    assert_eq!("kills[one]", issues[0].key);
+    assert_eq!(10, issues[0].line);
+    assert_eq!(
+        TermIssueKind::Outdated {
+            found: String::from("阿爾杜因")
+        },
+        issues[0].issue.kind
+    );
+}
+
+#[test]
+fn subtitles_transform_and_check() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("subtitles_transform_and_check.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let transform = |s: &str, format: SubtitleFormat| {
+        let mut output = Vec::new();
+
+        let count = dictionary.transform_subtitles(s.as_bytes(), &mut output, format).unwrap();
+
+        (count, String::from_utf8(output).unwrap())
+    };
+
+    let srt = "1
+00:00:01,000 --> 00:00:03,000
+{\\an8}<i>Alduin</i> returns
+
+2
+00:00:04,000 --> 00:00:06,000 X1:10
+Quit
+
+3
+00:00:07,000 --> 00:00:09,000
+Go to
+Aldun
+";
+
+    assert_eq!(
+        (
+            2,
+            String::from(
+                "1
+00:00:01,000 --> 00:00:03,000
+{\\an8}<i>奥杜因</i> returns
+
+2
+00:00:04,000 --> 00:00:06,000 X1:10
+Quit
+
+3
+00:00:07,000 --> 00:00:09,000
+Go to
+奧爾敦
+"
+            )
+        ),
+        transform(srt, SubtitleFormat::Srt)
+    );
+
+    let cues = read_subtitles(srt.as_bytes(), SubtitleFormat::Srt).unwrap();
+
+    assert_eq!(3, cues.len());
+    assert_eq!("Alduin returns", cues[0].text);
+    assert_eq!("00:00:06,000", cues[1].end);
+    assert_eq!("Go to\nAldun", cues[2].text);
+    assert_eq!(11, cues[2].line);
+
+    let translated = "1
+00:00:01,000 --> 00:00:03,000
+<i>阿爾杜因</i>回來了
+
+2
+00:00:04,000 --> 00:00:06,000
+離開
+
+3
+00:00:07,000 --> 00:00:09,000
+前往奧爾敦
+";
+
+    let issues = dictionary
+        .check_subtitles(srt.as_bytes(), translated.as_bytes(), SubtitleFormat::Srt)
+        .unwrap();
+
+    assert_eq!(1, issues.len());
+    assert_eq!(1, issues[0].index);
+    assert_eq!("00:00:01,000", issues[0].start);
+    assert_eq!(
+        TermIssueKind::Outdated {
+            found: String::from("阿爾杜因")
+        },
+        issues[0].issue.kind
+    );
+
+    assert!(read_subtitles("1\nAlduin\n".as_bytes(), SubtitleFormat::Srt).is_err());
+
+    let ass = "[Script Info]
+Title: Alduin
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Alduin
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,{\\i1}Alduin{\\i0}, returns\\NAldun
+";
+
+    assert_eq!(
+        (
+            1,
+            String::from(
+                "[Script Info]
+Title: Alduin
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+Comment: 0,0:00:00.00,0:00:01.00,Default,,0,0,0,,Alduin
+Dialogue: 0,0:00:01.00,0:00:03.00,Default,,0,0,0,,{\\i1}奥杜因{\\i0}, returns\\N奧爾敦
+"
+            )
+        ),
+        transform(ass, SubtitleFormat::Ass)
+    );
+
+    let cues = read_subtitles(ass.as_bytes(), SubtitleFormat::Ass).unwrap();
+
+    assert_eq!(1, cues.len());
+    assert_eq!("0:00:01.00", cues[0].start);
+    assert_eq!("Alduin, returns\nAldun", cues[0].text);
+
+    let vtt = "WEBVTT
+
+NOTE Alduin
+
+intro
+00:01.000 --> 00:03.000 line:0
+<v Narrator>Alduin &amp; <b>Aldun</b>
+";
+
+    assert_eq!(
+        (
+            1,
+            String::from(
+                "WEBVTT
+
+NOTE Alduin
+
+intro
+00:01.000 --> 00:03.000 line:0
+<v Narrator>奥杜因 &amp; <b>奧爾敦</b>
+"
+            )
+        ),
+        transform(vtt, SubtitleFormat::WebVtt)
+    );
+
+    let cues = read_subtitles(vtt.as_bytes(), SubtitleFormat::WebVtt).unwrap();
+
+    assert_eq!(1, cues.len());
+    assert_eq!("Alduin & Aldun", cues[0].text);
+
+    assert!(read_subtitles(srt.as_bytes(), SubtitleFormat::WebVtt).is_err());
+    assert_eq!(Some(SubtitleFormat::Ass), SubtitleFormat::from_extension("SSA"));
+}
+
+#[test]
+fn markup_transform() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("markup_transform.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+code = 程式碼
+title = 標題";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let html = r#"<p title="Alduin" class='code'>Alduin &amp; <a href="/Alduin">title</a><!-- Alduin --></p>
+<pre><code>Alduin</code></pre><SCRIPT>Alduin()</script><br/>code"#;
+
+    let options = TransformOptions {
+        markup: Markup::Html,
+        ..TransformOptions::default()
+    };
+
+    assert_eq!(
+        r#"<p title="Alduin" class='code'>奥杜因 &amp; <a href="/Alduin">標題</a><!-- Alduin --></p>
+<pre><code>Alduin</code></pre><SCRIPT>Alduin()</script><br/>程式碼"#,
+        dictionary.transform_with_options(html, &options)
+    );
+
+    let markdown = r#"# Alduin
+
+Call `Alduin()` or see [Alduin](https://example.com/Alduin "title") and https://example.com/Alduin.
+\*Alduin\* <span title="Alduin">Alduin</span>
+See [Alduin][title], [code][], [title] and <code>Alduin</code>.
+
+```rust
+Alduin
+```
+
+    Alduin
+
+[Alduin]: https://example.com/Alduin
+[Title]: https://example.com/title
+ [code]: https://example.com/code
+"#;
+
+    let options = TransformOptions {
+        markup: Markup::Markdown,
+        ..TransformOptions::default()
+    };
+
+    assert_eq!(
+        r#"# 奥杜因
+
+Call `Alduin()` or see [奥杜因](https://example.com/Alduin "title") and https://example.com/Alduin.
+\*奥杜因\* <span title="Alduin">奥杜因</span>
+See [奥杜因][title], [code][], [title] and <code>Alduin</code>.
+
+```rust
+Alduin
+```
+
+    Alduin
+
+[Alduin]: https://example.com/Alduin
+[Title]: https://example.com/title
+ [code]: https://example.com/code
+"#,
+        dictionary.transform_with_options(markdown, &options)
+    );
+
+    assert_eq!(
+        dictionary.transform(markdown),
+        dictionary.transform_with_options(markdown, &TransformOptions::default())
+    );
+}
+
+#[test]
+fn case_preserving_transform() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("case_preserving_transform.txt");
+
+    let dictionary_data = "Alduin = World-Eater
+Dovahkiin = dragonborn
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let options = TransformOptions {
+        case: CasePolicy::Preserve,
+        ..TransformOptions::default()
+    };
+
+    assert_eq!(
+        "WORLD-EATER, world-eater, World-Eater, World-Eater, Dragonborn, DRAGONBORN, 奧爾敦",
+        dictionary.transform_with_options(
+            "ALDUIN, alduin, Alduin, aLDUIN, Dovahkiin, DOVAHKIIN, ALDUN",
+            &options
+        )
+    );
+
+    assert_eq!(
+        "World-Eater, World-Eater, dragonborn",
+        dictionary.transform("ALDUIN, alduin, Dovahkiin")
+    );
+
+    assert_eq!("Élan", CasePolicy::Preserve.apply("X", "élan"));
+    assert_eq!("élan", CasePolicy::Keep.apply("X", "élan"));
+}
+
+#[test]
+fn word_boundary_transform() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("word_boundary_transform.txt");
+
+    let dictionary_data = "ability = 技能
+Aldun = 奧爾敦
+heim = 海姆
+阿爾 = 阿爾杜因";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let text = "Aldunheim, disability, Aldun's ability, 前往Aldun。阿爾的";
+
+    assert_eq!(
+        "奧爾敦海姆, dis技能, 奧爾敦's 技能, 前往奧爾敦。阿爾杜因的",
+        dictionary.transform(text)
+    );
+
+    let mut options = TransformOptions {
+        boundary: BoundaryRule::Word,
+        ..TransformOptions::default()
+    };
+
+    assert_eq!(
+        "Aldunheim, disability, 奧爾敦's 技能, 前往奧爾敦。阿爾杜因的",
+        dictionary.transform_with_options(text, &options)
+    );
+
+    options.entry_boundaries.insert(String::from("heim"), BoundaryRule::Substring);
+
+    assert_eq!(
+        "Aldun海姆, disability, 奧爾敦's 技能, 前往奧爾敦。阿爾杜因的",
+        dictionary.transform_with_options(text, &options)
+    );
+
+    let occurrences = dictionary.scan(text, &options);
+
+    assert_eq!(5, occurrences.len());
+    assert_eq!(5..9, occurrences[0].range);
+    assert_eq!(Some("heim"), dictionary.get_left(occurrences[0].index));
+}
+
+#[test]
+fn inflection_aware_transform() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("inflection_aware_transform.txt");
+
+    let dictionary_data = "Draugr = 屍鬼
+Dragonborn = 龍裔
+Thalmor = 薩爾默
+Dovahkiin = dragonborn
+Aldun = 奧爾敦";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let text = "Draugr's, Dragonborns, Thalmor-controlled, Dovahkiins', Dovahkiin's, Aldunes, \
+                Dragonbornsss";
+
+    let options = TransformOptions {
+        boundary: BoundaryRule::Word,
+        inflection: true,
+        ..TransformOptions::default()
+    };
+
+    let occurrences = dictionary.scan(text, &options);
+
+    assert_eq!(
+        vec![
+            (0..8, Some(Inflection::Possessive)),
+            (10..21, Some(Inflection::Plural)),
+            (23..30, Some(Inflection::Compound)),
+            (43..54, Some(Inflection::PluralPossessive)),
+            (56..67, Some(Inflection::Possessive)),
+        ],
+        occurrences.iter().map(|o| (o.range.clone(), o.inflection)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        "屍鬼's, 龍裔, 薩爾默-controlled, dragonborns', dragonborn's, Aldunes, Dragonbornsss",
+        dictionary.transform_with_options(text, &options)
+    );
+
+    let options = TransformOptions {
+        boundary: BoundaryRule::Word,
+        ..TransformOptions::default()
+    };
+
+    assert_eq!(
+        "屍鬼's, Dragonborns, 薩爾默-controlled, Dovahkiins', dragonborn's, Aldunes, Dragonbornsss",
+        dictionary.transform_with_options(text, &options)
+    );
+
+    assert!(dictionary.scan(text, &options).iter().all(|o| o.inflection.is_none()));
+}
+
+#[test]
+fn term_extraction() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("term_extraction.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Dragon Priest = 龍祭司";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let texts = [
+        "Alduin waits at the Throat of the World. The Dragon Priest guards the word wall.",
+        "Climb the Throat of the World to find the word wall.",
+        "Paarthurnax lives on the Throat of the World, near another word wall. Dragon Priest!",
+    ];
+
+    let candidates = dictionary.extract_terms(texts, &ExtractionOptions::default());
+
+    assert_eq!(
+        vec![
+            ("Throat of the World", TermCandidateKind::Name, 3),
+            ("word wall", TermCandidateKind::NGram, 3)
+        ],
+        candidates.iter().map(|c| (c.term.as_str(), c.kind, c.frequency)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![
+            "Alduin waits at the Throat of the World. The Dragon Priest guards the",
+            "Climb the Throat of the World to find the word wall.",
+            "Paarthurnax lives on the Throat of the World, near another word wall."
+        ],
+        candidates[0].contexts
+    );
+
+    let options = ExtractionOptions {
+        min_frequency: 1,
+        max_contexts: 1,
+        ..ExtractionOptions::default()
+    };
+
+    let candidates = dictionary.extract_terms(texts, &options);
+
+    assert!(candidates.iter().any(|c| c.term == "word wall" && c.contexts.len() == 1));
+    assert!(candidates.iter().any(|c| c.term == "find the word"));
+    assert!(!candidates.iter().any(|c| c.term == "the word" || c.term == "Dragon Priest"));
+}
+
+#[test]
+fn matcher_find_all() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("matcher_find_all.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Dragon = 龍
+Dragon Priest = 龍祭司";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    assert_eq!(0, dictionary.matcher().pattern_count());
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(7, dictionary.matcher().pattern_count());
+
+    let text = "the dragon priest 阿爾杜因";
+
+    let index = dictionary.find_left_strictly("Dragon Priest", 0).unwrap();
+
+    let matches = dictionary.matcher().find_all(text);
+
+    assert_eq!(3, matches.len());
+    assert_eq!((4..17, index, MatchTarget::Left), {
+        let m = &matches[0];
+
+        (m.range.clone(), m.index, m.target)
+    });
+    assert_eq!(4..10, matches[1].range);
+    assert_eq!(
+        MatchTarget::Right {
+            history_index: 0
+        },
+        matches[2].target
+    );
+    assert_eq!(Some("Alduin"), dictionary.get_left(matches[2].index));
+
+    assert_eq!("the 龍祭司 阿爾杜因", dictionary.transform(text));
+
+    dictionary.add_edit("Priest", "祭司").unwrap();
+    dictionary.delete(dictionary.find_left_strictly("Dragon Priest", 0).unwrap()).unwrap();
+
+    assert_eq!(7, dictionary.matcher().pattern_count());
+    assert_eq!("the 龍 祭司 阿爾杜因", dictionary.transform(text));
+}
+
+#[test]
+fn search_iterators() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("search_iterators.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦
+Dun = 敦 --> 阿爾杜因";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(
+        vec![("Alduin", 2), ("Aldun", 1), ("Dun", 2)],
+        dictionary.iter().map(|(left, right)| (left, right.len())).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![(1, MatchKind::Substring), (2, MatchKind::Exact)],
+        dictionary.search_left("dun").map(|m| (m.index, m.kind)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![
+            (0, MatchKind::Exact, MatchTarget::Right {
+                history_index: 0
+            }),
+            (2, MatchKind::Exact, MatchTarget::Right {
+                history_index: 1
+            }),
+        ],
+        dictionary
+            .search_right("阿爾杜因")
+            .map(|m| (m.index, m.kind, m.target))
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(2, dictionary.search_right("敦").count());
+    assert_eq!(0, dictionary.search_left("Skyrim").count());
+}
+
+#[test]
+fn ranked_search() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("ranked_search.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦
+Dun = 敦 --> 阿爾杜因
+Dunmer = 丹莫
+Redundant = 多餘";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let results = dictionary.search("dun", &SearchOptions::default());
+
+    assert_eq!(
+        vec![
+            ("Dun", MatchKind::Exact),
+            ("Dunmer", MatchKind::Prefix),
+            ("Aldun", MatchKind::Substring),
+            ("Redundant", MatchKind::Substring)
+        ],
+        results.iter().map(|r| (dictionary.get_left(r.index).unwrap(), r.kind)).collect::<Vec<_>>()
+    );
+
+    let results = dictionary.search("Alduim", &SearchOptions::default());
+
+    assert_eq!(
+        vec![("Alduin", MatchKind::Fuzzy), ("Aldun", MatchKind::Fuzzy)],
+        results.iter().map(|r| (dictionary.get_left(r.index).unwrap(), r.kind)).collect::<Vec<_>>()
+    );
+
+    // the current right word of `Dun` ranks above the old right word of `Alduin`
+    let results = dictionary.search("阿爾杜因", &SearchOptions::default());
+
+    assert_eq!(
+        vec![
+            (2, MatchTarget::Right {
+                history_index: 1
+            }),
+            (0, MatchTarget::Right {
+                history_index: 0
+            })
+        ],
+        results.iter().map(|r| (r.index, r.target)).collect::<Vec<_>>()
+    );
+
+    let options = SearchOptions {
+        left_weight: 1.0,
+        right_weight: 10.0,
+        max_distance: 0,
+        limit: Some(1),
+        ..SearchOptions::default()
+    };
+
+    assert!(dictionary.search("Alduim", &options).is_empty());
+    assert_eq!(
+        vec![(0, MatchKind::Exact)],
+        dictionary.search("奥杜因", &options).iter().map(|r| (r.index, r.kind)).collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn regex_search() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("regex_search.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Markarth = 馬卡斯
+Riften = 裂谷城
+Solitude = 孤獨城
+Windhelm = 風盔城
+Winterhold = 凜冬堡
+Whiterun = 白漫城 --> 雪漫城";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let regex = Regex::new("(?i)^w.*(helm|hold)$").unwrap();
+
+    assert_eq!(
+        vec!["Windhelm", "Winterhold"],
+        dictionary
+            .find_left_regex_iter(&regex)
+            .map(|index| dictionary.get_left(index).unwrap())
+            .collect::<Vec<_>>()
+    );
+
+    let windhelm = dictionary.find_left_regex(&regex, 0).unwrap();
+
+    assert_eq!(Some(windhelm + 1), dictionary.find_left_regex(&regex, windhelm + 1));
+    assert_eq!(Some(windhelm), dictionary.find_left_regex(&regex, windhelm + 2));
+    assert_eq!(
+        dictionary.find_left_regex(&regex, usize::MAX % dictionary.count()),
+        dictionary.find_left_regex(&regex, usize::MAX)
+    );
+
+    let regex = Regex::new("爾.*杜").unwrap();
+
+    assert_eq!(
+        vec![(0, 0)],
+        dictionary.find_right_regex_iter(&regex, RevisionScope::History).collect::<Vec<_>>()
+    );
+    assert_eq!(None, dictionary.find_right_regex(&regex, 0, RevisionScope::Current));
+    assert_eq!(Some(0), dictionary.find_right_regex(&regex, usize::MAX, RevisionScope::History));
+
+    let regex = Regex::new("城$").unwrap();
+
+    assert_eq!(4, dictionary.find_right_regex_iter(&regex, RevisionScope::Current).count());
+    assert_eq!(
+        Some((6, 1)),
+        dictionary.find_right_regex_iter(&regex, RevisionScope::History).last()
+    );
+}
+
+#[test]
+fn prefix_completion() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("prefix_completion.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦
+Alftand = 阿夫坦德
+Markarth = 馬卡斯";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(
+        vec!["Aldun", "Alduin"],
+        dictionary.complete_left("ALD", 10).into_iter().map(|c| c.word).collect::<Vec<_>>()
+    );
+    assert_eq!(1, dictionary.complete_left("al", 1).len());
+    assert!(dictionary.complete_right("阿爾", 10).is_empty());
+
+    dictionary.set_completion_ranking(CompletionRanking::Alphabetical);
+
+    dictionary.add_edit("Alduin", "阿爾杜因").unwrap();
+    dictionary.add_edit("Alcove", "壁龕").unwrap();
+    dictionary.delete(dictionary.find_left_strictly("Aldun", 0).unwrap()).unwrap();
+
+    assert_eq!(
+        vec!["Alcove", "Alduin", "Alftand"],
+        dictionary.complete_left("al", 10).into_iter().map(|c| c.word).collect::<Vec<_>>()
+    );
+
+    let completions = dictionary.complete_right("阿爾", 10);
+
+    assert_eq!(1, completions.len());
+    assert_eq!(Some("Alduin"), dictionary.get_left(completions[0].index));
+    assert_eq!(
+        MatchTarget::Right {
+            history_index: 2
+        },
+        completions[0].target
+    );
+
+    dictionary.set_completion_ranking(CompletionRanking::Index);
+
+    assert_eq!(
+        vec![0, 1],
+        dictionary.complete_left("al", 2).into_iter().map(|c| c.index).collect::<Vec<_>>()
+    );
+}
+
+#[cfg(feature = "pinyin")]
+#[test]
+fn pinyin_search() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("pinyin_search.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Aldun = 奧爾敦
+Dun = 敦 --> 阿爾杜因
+Dunmer = 丹莫";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    for query in ["a er du yin", "AErDuYin", "a'er'du'yin"] {
+        assert_eq!(
+            vec![
+                (0, MatchKind::Exact, MatchTarget::Right {
+                    history_index: 0
+                }),
+                (2, MatchKind::Exact, MatchTarget::Right {
+                    history_index: 1
+                }),
+            ],
+            dictionary
+                .search_right_pinyin(query)
+                .map(|m| (m.index, m.kind, m.target))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    assert_eq!(
+        vec![(0, MatchKind::Substring), (2, MatchKind::Substring)],
+        dictionary.search_right_pinyin("aedy").map(|m| (m.index, m.kind)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![
+            (0, MatchTarget::Right {
+                history_index: 1
+            }),
+            (2, MatchTarget::Right {
+                history_index: 1
+            }),
+        ],
+        dictionary.search_right_pinyin("duyin").map(|m| (m.index, m.target)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![(1, MatchKind::Substring), (2, MatchKind::Exact)],
+        dictionary.search_right_pinyin("dun").map(|m| (m.index, m.kind)).collect::<Vec<_>>()
+    );
+
+    assert_eq!(Some(3), dictionary.find_right_pinyin("dm", 0));
+    assert_eq!(Some(3), dictionary.find_right_pinyin("danmo", 0));
+    assert_eq!(Some(2), dictionary.find_right_pinyin("aedy", 1));
+    assert_eq!(Some(0), dictionary.find_right_pinyin("ady", 1));
+    assert_eq!(None, dictionary.find_right_pinyin("阿", 0));
+    assert_eq!(None, dictionary.find_right_pinyin("xyz", 0));
+}
+
+#[test]
+fn similar_sounding() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("similar_sounding.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Dunmer = 丹莫
+Philip = 菲利普
+Whiterun = 白漫城";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(double_metaphone("Alduin").primary, double_metaphone("Aldwin").primary);
+    assert!(double_metaphone("Philip").sounds_like(&double_metaphone("Filip")));
+    assert!(!double_metaphone("Dunmer").sounds_like(&double_metaphone("Alduin")));
+
+    assert_eq!(
+        vec![(0, MatchKind::Exact, MatchTarget::Left)],
+        dictionary
+            .find_similar_sounding("Aldwin")
+            .map(|m| (m.index, m.kind, m.target))
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![2],
+        dictionary.find_similar_sounding("Fillip").map(|m| m.index).collect::<Vec<_>>()
+    );
+    assert_eq!(
+        vec![3],
+        dictionary.find_similar_sounding("Whiteran").map(|m| m.index).collect::<Vec<_>>()
+    );
+    assert_eq!(0, dictionary.find_similar_sounding("Skyrim").count());
+}
+
+#[cfg(feature = "pinyin")]
+#[test]
+fn similar_sounding_pinyin() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("similar_sounding_pinyin.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因 --> 奥杜因
+Dunmer = 丹莫
+Solitude = 獨孤城";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(Some(String::from("aerduyin")), dictionary.pinyin_key("阿爾杜因"));
+    assert_eq!(dictionary.pinyin_key("獨孤城"), dictionary.pinyin_key("獨孤岑"));
+    assert_eq!(None, dictionary.pinyin_key("Alduin"));
+
+    assert_eq!(
+        vec![(0, MatchKind::Exact, MatchTarget::Right {
+            history_index: 1
+        })],
+        dictionary
+            .find_similar_sounding("奧杜因")
+            .map(|m| (m.index, m.kind, m.target))
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![(0, MatchKind::Fuzzy, MatchTarget::Right {
+            history_index: 1
+        })],
+        dictionary
+            .find_similar_sounding("阿杜因")
+            .map(|m| (m.index, m.kind, m.target))
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![2],
+        dictionary.find_similar_sounding("獨孤岑").map(|m| m.index).collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn duplicates() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("duplicates.txt");
+
+    let dictionary_data = "Dwarven Ruin = 矮人遺跡
+Dwarven-Ruin = 矮人廢墟 --> 矮人遺跡
+Dwemer = 德魏默
+Dwemmer = 德維莫 --> 德魏默
+Philip = 菲利普
+Filip = 菲力普
+Skyrim = 天際";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    let clusters = dictionary.find_duplicates(&DuplicateOptions::default());
+
+    assert_eq!(
+        vec![vec!["Dwarven Ruin", "Dwarven-Ruin"], vec!["Dwemer", "Dwemmer"], vec![
+            "Philip", "Filip"
+        ]],
+        clusters
+            .iter()
+            .map(|cluster| cluster
+                .indices
+                .iter()
+                .map(|&index| dictionary.get_left(index).unwrap())
+                .collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+    );
+
+    assert_eq!(
+        vec![
+            DuplicateReason::Normalized,
+            DuplicateReason::EditDistance(1),
+            DuplicateReason::Phonetic
+        ],
+        clusters.iter().map(|cluster| cluster.links[0].reason).collect::<Vec<_>>()
+    );
+
+    assert!(dictionary
+        .find_duplicates(&DuplicateOptions {
+            max_distance: 0, phonetic: false
+        })
+        .iter()
+        .all(|cluster| cluster.links[0].reason == DuplicateReason::Normalized));
+
+    assert!(matches!(dictionary.merge_entries(4, 5), Err(MergeEntriesError::Conflict { .. })));
+    assert!(!dictionary.merge_entries(0, 0).unwrap());
+    assert!(dictionary.merge_entries(2, 3).unwrap());
+
+    assert_eq!(6, dictionary.count());
+
+    let index = dictionary.find_left_strictly("Dwemer", 0).unwrap();
+
+    assert_eq!(Some("德維莫 --> 德魏默"), dictionary.get_all_right_to_string(index).as_deref());
+    assert_eq!(None, dictionary.find_left_strictly("Dwemmer", 0));
+
+    assert_eq!(
+        "Dwarven Ruin = 矮人遺跡
+Dwarven-Ruin = 矮人廢墟 --> 矮人遺跡
+Dwemer = 德維莫 --> 德魏默
+Filip = 菲力普
+Philip = 菲利普
+Skyrim = 天際",
+        fs::read_to_string(&dictionary_path).unwrap()
+    );
+}
+
+#[test]
+fn merge_dictionaries() {
+    let ours_data = "Alduin = 阿爾杜因 --> 奥杜因
+Dwemer = 德維莫 --> 德魏默
+Skyrim = 天際";
+
+    let theirs_data = "alduin = 阿杜因 --> 奥杜因
+Dwemer = 德維莫 --> 德威默
+Riften = 裂谷城";
+
+    let theirs_path = Path::new(DIRECTORY_PATH).join("merge_dictionaries_theirs.txt");
+
+    fs::write(&theirs_path, theirs_data).unwrap();
+
+    let mut theirs = Dictionary::new(&theirs_path);
+
+    theirs.read_data().unwrap();
+
+    let merge = |name: &str, strategy: MergeStrategy| {
+        let dictionary_path = Path::new(DIRECTORY_PATH).join(name);
+
+        fs::write(&dictionary_path, ours_data).unwrap();
+
+        let mut dictionary = Dictionary::new(&dictionary_path);
+
+        dictionary.read_data().unwrap();
+
+        let report = dictionary.merge(&theirs, strategy).unwrap();
+
+        (report, fs::read_to_string(&dictionary_path).unwrap())
+    };
+
+    let (report, data) = merge("merge_dictionaries_collect.txt", MergeStrategy::Collect);
+
+    assert_eq!(1, report.added);
+    assert_eq!(1, report.updated);
+    assert_eq!(
+        vec![MergeConflict {
+            index:               1,
+            left_string:         String::from("Dwemer"),
+            right_strings:       vec![String::from("德維莫"), String::from("德魏默")],
+            other_right_strings: vec![String::from("德維莫"), String::from("德威默")],
+        }],
+        report.conflicts
+    );
+    assert_eq!(
+        "Alduin = 阿爾杜因 --> 阿杜因 --> 奥杜因
+Dwemer = 德維莫 --> 德魏默
+Riften = 裂谷城
+Skyrim = 天際",
+        data
+    );
+
+    let (_, data) = merge("merge_dictionaries_ours.txt", MergeStrategy::KeepOurs);
+
+    assert!(data.contains("Dwemer = 德維莫 --> 德威默 --> 德魏默\n"));
+
+    let (_, data) = merge("merge_dictionaries_theirs_kept.txt", MergeStrategy::KeepTheirs);
+
+    assert!(data.contains("Dwemer = 德維莫 --> 德魏默 --> 德威默\n"));
+
+    let (report, data) = merge("merge_dictionaries_append.txt", MergeStrategy::AppendTheirs);
+
+    assert_eq!(2, report.updated);
+    assert_eq!(1, report.conflicts.len());
+    assert!(data.contains("Dwemer = 德維莫 --> 德魏默 --> 德威默\n"));
+}
+
+#[test]
+fn dictionary_diff() {
+    let old_path = Path::new(DIRECTORY_PATH).join("dictionary_diff_old.txt");
+    let new_path = Path::new(DIRECTORY_PATH).join("dictionary_diff_new.txt");
+
+    fs::write(
+        &old_path,
+        "Alduin = 阿爾杜因 --> 奥杜因
+Dwemer = 德魏默
+Skyrim = 天際
+Whiterun = 白漫城",
+    )
+    .unwrap();
+
+    fs::write(
+        &new_path,
+        "alduin = 阿爾杜因 --> 阿杜因 --> 奥杜因
+Dwemer = 德魏默 --> 德威默
+Riften = 裂谷城
+Whiterun = 白漫城",
+    )
+    .unwrap();
+
+    let mut old = Dictionary::new(&old_path);
+    let mut new = Dictionary::new(&new_path);
+
+    old.read_data().unwrap();
+    new.read_data().unwrap();
+
+    let diff = old.diff(&new);
+
+    assert_eq!(
+        EntryChange::Retranslated {
+            left:      String::from("Dwemer"),
+            old_right: vec![String::from("德魏默")],
+            new_right: vec![String::from("德魏默"), String::from("德威默")],
+        },
+        diff.changes[1]
+    );
+
+    assert_eq!(
+        "history changed alduin = 阿爾杜因 --> 奥杜因 => 阿爾杜因 --> 阿杜因 --> 奥杜因
+retranslated Dwemer = 德魏默 => 德威默
+added Riften = 裂谷城
+removed Skyrim = 天際",
+        diff.to_string()
+    );
+
+    assert!(new.diff(&new).is_empty());
+    assert_eq!(4, new.diff(&old).changes.len());
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn dictionary_diff_json() {
+    let old_path = Path::new(DIRECTORY_PATH).join("dictionary_diff_json_old.txt");
+    let new_path = Path::new(DIRECTORY_PATH).join("dictionary_diff_json_new.txt");
+
+    fs::write(&old_path, "Dwemer = 德魏默").unwrap();
+    fs::write(&new_path, "Dwemer = 德魏默 --> 德威默").unwrap();
+
+    let mut old = Dictionary::new(&old_path);
+    let mut new = Dictionary::new(&new_path);
+
+    old.read_data().unwrap();
+    new.read_data().unwrap();
+
+    let mut json = Vec::new();
+
+    old.diff(&new).export_json(&mut json).unwrap();
+
+    assert_eq!(
+        r#"{
+  "changes": [
+    {
+      "kind": "retranslated",
+      "left": "Dwemer",
+      "old_right": [
+        "德魏默"
+      ],
+      "new_right": [
+        "德魏默",
+        "德威默"
+      ]
+    }
+  ]
+}"#,
+        String::from_utf8(json).unwrap()
+    );
+}
+
+#[test]
+fn three_way_merge() {
+    let base_path = Path::new(DIRECTORY_PATH).join("three_way_merge_base.txt");
+    let ours_path = Path::new(DIRECTORY_PATH).join("three_way_merge_ours.txt");
+    let theirs_path = Path::new(DIRECTORY_PATH).join("three_way_merge_theirs.txt");
+
+    fs::write(
+        &base_path,
+        "Alduin = 阿爾杜因
+Dwemer = 德魏默
+Riften = 裂谷城
+Skyrim = 天際
+Whiterun = 白漫城",
+    )
+    .unwrap();
+
+    fs::write(
+        &ours_path,
+        "Alduin = 阿爾杜因 --> 奥杜因
+Dwemer = 德魏默 --> 德威默
+Skyrim = 天際
+Solitude = 獨孤城
+Whiterun = 白漫城",
+    )
+    .unwrap();
+
+    fs::write(
+        &theirs_path,
+        "Alduin = 阿爾杜因 --> 奥杜因
+Dwemer = 德魏默 --> 德維莫
+Markarth = 馬卡斯城
+Skyrim = 天際省
+Whiterun = 白漫城",
+    )
+    .unwrap();
+
+    let mut base = Dictionary::new(&base_path);
+    let mut ours = Dictionary::new(&ours_path);
+    let mut theirs = Dictionary::new(&theirs_path);
+
+    base.read_data().unwrap();
+    ours.read_data().unwrap();
+    theirs.read_data().unwrap();
+
+    let conflicts = ours.merge_three_way(&base, &theirs).unwrap();
+
+    assert_eq!(1, conflicts.len());
+    assert_eq!("Dwemer", conflicts[0].left_string);
+
+    assert_eq!(
+        "Alduin = 阿爾杜因 --> 奥杜因
+<<<<<<< ours
+Dwemer = 德魏默 --> 德威默
+=======
+Dwemer = 德魏默 --> 德維莫
+>>>>>>> theirs
+Markarth = 馬卡斯城
+Skyrim = 天際省
+Solitude = 獨孤城
+Whiterun = 白漫城",
+        fs::read_to_string(&ours_path).unwrap()
+    );
+
+    fs::write(&ours_path, "Alduin = 阿爾杜因 --> 奥杜因").unwrap();
+    fs::write(&theirs_path, "Alduin = 阿爾杜因 --> 阿杜因 --> 奥杜因\nDwemer = 德魏默 --> 德維莫")
+        .unwrap();
+
+    let mut ours = Dictionary::new(&ours_path);
+    let mut theirs = Dictionary::new(&theirs_path);
+
+    ours.read_data().unwrap();
+    theirs.read_data().unwrap();
+
+    let conflicts = ours.merge_three_way(&base, &theirs).unwrap();
+
+    assert_eq!(1, conflicts.len());
+    assert_eq!(None, conflicts[0].right_strings);
+
+    assert_eq!(
+        "Alduin = 阿爾杜因 --> 阿杜因 --> 奥杜因
+<<<<<<< ours
+=======
+Dwemer = 德魏默 --> 德維莫
+>>>>>>> theirs",
+        fs::read_to_string(&ours_path).unwrap()
+    );
+}
+
+#[test]
+fn undo_redo() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("undo_redo.txt");
+
+    let dictionary_data = "Alduin = 阿爾杜因
+Dwemer = 德魏默
+Dwemmer = 德魏默";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(None, dictionary.can_undo());
+    assert_eq!(None, dictionary.undo().unwrap());
+
+    dictionary.add_edit("Alduin", "奥杜因").unwrap();
+    dictionary.delete(dictionary.find_left_strictly("Dwemer", 0).unwrap()).unwrap();
+
+    assert_eq!(Some(OperationKind::Delete), dictionary.can_undo());
+    assert_eq!(Some(OperationKind::Delete), dictionary.undo().unwrap());
+    assert_eq!(Some(OperationKind::AddEdit), dictionary.undo().unwrap());
+    assert_eq!(None, dictionary.undo().unwrap());
+
+    assert_eq!(dictionary_data, fs::read_to_string(&dictionary_path).unwrap());
+
+    assert_eq!(Some(OperationKind::AddEdit), dictionary.redo().unwrap());
+
+    assert_eq!(
+        "Alduin = 阿爾杜因 --> 奥杜因
+Dwemer = 德魏默
+Dwemmer = 德魏默",
+        fs::read_to_string(&dictionary_path).unwrap()
+    );
+
+    dictionary.merge_entries(1, 2).unwrap();
+
+    // a new operation drops the undone ones
+    assert_eq!(None, dictionary.can_redo());
+    assert_eq!(Some(OperationKind::MergeEntries), dictionary.undo().unwrap());
+    assert_eq!(3, dictionary.count());
+    assert_eq!(
+        Some("德魏默"),
+        dictionary.get_right(dictionary.find_left_strictly("Dwemmer", 0).unwrap())
+    );
+
+    dictionary.set_undo_depth(1);
+
+    assert_eq!(Some(OperationKind::AddEdit), dictionary.undo().unwrap());
+    assert_eq!(None, dictionary.undo().unwrap());
+
+    dictionary.set_undo_depth(0);
+
+    dictionary.add_edit("Riften", "裂谷城").unwrap();
+
+    assert_eq!(None, dictionary.can_undo());
+    assert_eq!(None, dictionary.can_redo());
+}
+
+#[cfg(feature = "pinyin")]
+#[test]
+fn pinyin_search_non_table_characters() {
+    let dictionary_path = Path::new(DIRECTORY_PATH).join("pinyin_search_non_table_characters.txt");
+
+    let dictionary_data = "Cafe = Café
+Alduin = 阿爾杜因
+Kana = カナ";
+
+    fs::write(&dictionary_path, dictionary_data).unwrap();
+
+    let mut dictionary = Dictionary::new(&dictionary_path);
+
+    dictionary.read_data().unwrap();
+
+    assert_eq!(None, dictionary.find_right_pinyin("x", 0));
+    assert_eq!(Some(0), dictionary.find_right_pinyin("caf", 0));
+    assert_eq!(Some(1), dictionary.find_right_pinyin("aedy", usize::MAX));
+    assert_eq!(0, dictionary.search_right_pinyin("k").count());
+}
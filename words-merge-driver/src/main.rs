@@ -0,0 +1,49 @@
+use std::{env, process};
+
+use word_dictionary::*;
+
+/// The exit code for conflicts, which git treats as an unresolved merge.
+const EXIT_CONFLICT: i32 = 1;
+/// The exit code for bad arguments or unreadable files.
+const EXIT_ERROR: i32 = 2;
+
+fn read_dictionary(path: &str) -> Dictionary {
+    let mut dictionary = Dictionary::new(path);
+
+    if let Err(err) = dictionary.read_data() {
+        eprintln!("{}: {}", path, err);
+        process::exit(EXIT_ERROR);
+    }
+
+    dictionary
+}
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() != 4 {
+        eprintln!("Usage: {} <ancestor %O> <ours %A> <theirs %B>", args[0]);
+        process::exit(EXIT_ERROR);
+    }
+
+    let base = read_dictionary(&args[1]);
+    let mut ours = read_dictionary(&args[2]);
+    let theirs = read_dictionary(&args[3]);
+
+    // the result is written to our file, as git expects
+    match ours.merge_three_way(&base, &theirs) {
+        Ok(conflicts) => {
+            if !conflicts.is_empty() {
+                for conflict in conflicts {
+                    eprintln!("{}: {}", args[2], conflict);
+                }
+
+                process::exit(EXIT_CONFLICT);
+            }
+        },
+        Err(err) => {
+            eprintln!("{}: {}", args[2], err);
+            process::exit(EXIT_ERROR);
+        },
+    }
+}
@@ -22,6 +22,8 @@ const CONTROL_SPACING: u16 = 10;
 const INPUT_PADDING: u16 = 8;
 const BUTTON_WIDTH: u16 = 100;
 const BUTTON_HEIGHT: u16 = 36;
+const SUGGESTION_LIMIT: usize = 5;
+const SUGGESTION_FONT_SIZE: u16 = 16;
 
 lazy_static_include_bytes! {
     /// Source Han Sans HW TC Regular
@@ -37,10 +39,36 @@ enum FoundState {
     Found,
 }
 
+/// A found entry, ranked by `Dictionary::search` or found by pinyin.
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum FoundMatch {
+    Ranked(SearchResult),
+    Pinyin(Match),
+}
+
+impl FoundMatch {
+    #[inline]
+    fn index(&self) -> usize {
+        match self {
+            FoundMatch::Ranked(result) => result.index,
+            FoundMatch::Pinyin(m) => m.index,
+        }
+    }
+
+    #[inline]
+    fn target(&self) -> MatchTarget {
+        match self {
+            FoundMatch::Ranked(result) => result.target,
+            FoundMatch::Pinyin(m) => m.target,
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 struct UIStates {
     keyword:            String,
     keyword_state:      text_input::State,
+    suggestions:        Vec<String>,
     result:             String,
     result_state:       text_input::State,
     evolution:          String,
@@ -59,11 +87,11 @@ struct UIStates {
 
 #[derive(Debug)]
 struct WordsTransformer {
-    dictionary:  Dictionary,
-    find_index:  usize,
-    find_left:   bool,
-    found_state: FoundState,
-    ui_states:   UIStates,
+    dictionary:   Dictionary,
+    matches:      Vec<FoundMatch>,
+    match_cursor: usize,
+    found_state:  FoundState,
+    ui_states:    UIStates,
 }
 
 impl WordsTransformer {
@@ -78,11 +106,23 @@ impl WordsTransformer {
     }
 
     fn no_search(&mut self) {
+        self.matches.clear();
+        self.match_cursor = 0;
         self.ui_states.result.clear();
         self.ui_states.evolution.clear();
         self.found_state = FoundState::Default;
     }
 
+    fn suggest(&mut self) {
+        let s = self.ui_states.keyword.trim();
+
+        self.ui_states.suggestions = if s.is_empty() {
+            Vec::new()
+        } else {
+            self.dictionary.complete_left(s, SUGGESTION_LIMIT).into_iter().map(|c| c.word).collect()
+        };
+    }
+
     fn search(&mut self) {
         let s = self.ui_states.keyword.trim();
 
@@ -90,49 +130,32 @@ impl WordsTransformer {
             return;
         }
 
-        let (find_index, find_left) = match self.dictionary.find_left_strictly(s, 0) {
-            Some(find_index) => (find_index, true),
-            None => {
-                match self.dictionary.find_left(s, 0) {
-                    Some(find_index) => (find_index, true),
-                    None => {
-                        match self.dictionary.find_right_strictly(s, 0) {
-                            Some(find_index) => (find_index, false),
-                            None => {
-                                match self.dictionary.find_right(s, 0) {
-                                    Some(find_index) => (find_index, false),
-                                    None => {
-                                        // not found
-                                        self.ui_states.result =
-                                            String::from("---Cannot find the word!---");
-                                        self.ui_states.evolution.clear();
-                                        self.found_state = FoundState::NotFound;
-
-                                        return;
-                                    },
-                                }
-                            },
-                        }
-                    },
-                }
-            },
-        };
+        self.matches = self
+            .dictionary
+            .search(s, &SearchOptions::default())
+            .into_iter()
+            .map(FoundMatch::Ranked)
+            .collect();
+
+        // romanized chinese words are found after the other matches
+        for m in self.dictionary.search_right_pinyin(s) {
+            if self.matches.iter().all(|found| found.index() != m.index) {
+                self.matches.push(FoundMatch::Pinyin(m));
+            }
+        }
 
-        self.find_index = find_index;
-        self.find_left = find_left;
+        self.match_cursor = 0;
 
-        self.ui_states.result = if find_left {
-            self.dictionary.get_left(find_index).unwrap()
-        } else {
-            self.dictionary.get_right(find_index).unwrap()
+        if self.matches.is_empty() {
+            // not found
+            self.ui_states.result = String::from("---Cannot find the word!---");
+            self.ui_states.evolution.clear();
+            self.found_state = FoundState::NotFound;
+
+            return;
         }
-        .to_string();
 
-        self.ui_states.evolution = format!(
-            "{} = {}",
-            self.dictionary.get_left(find_index).unwrap(),
-            self.dictionary.get_all_right_to_string(find_index).unwrap()
-        );
+        self.show_match();
 
         self.found_state = FoundState::Found;
     }
@@ -144,54 +167,31 @@ impl WordsTransformer {
     }
 
     fn search_next(&mut self) {
-        let s = self.ui_states.keyword.trim();
-
-        let find_index = self.find_index + 1;
+        if self.matches.is_empty() {
+            return;
+        }
 
-        let (find_index, find_left) = if self.find_left {
-            let next_index = self.dictionary.find_left(s, find_index).unwrap();
+        self.match_cursor = (self.match_cursor + 1) % self.matches.len();
 
-            if next_index == self.find_index {
-                match self.dictionary.find_right_strictly(s, find_index) {
-                    Some(find_index) => (find_index, false),
-                    None => match self.dictionary.find_right(s, find_index) {
-                        Some(find_index) => (find_index, false),
-                        None => (next_index, true),
-                    },
-                }
-            } else {
-                (next_index, true)
-            }
-        } else {
-            let next_index = self.dictionary.find_right(s, find_index).unwrap();
-
-            if next_index == self.find_index {
-                match self.dictionary.find_left_strictly(s, find_index) {
-                    Some(find_index) => (find_index, true),
-                    None => match self.dictionary.find_left(s, find_index) {
-                        Some(find_index) => (find_index, true),
-                        None => (next_index, false),
-                    },
-                }
-            } else {
-                (next_index, false)
-            }
-        };
+        self.show_match();
+    }
 
-        self.find_index = find_index;
-        self.find_left = find_left;
+    fn show_match(&mut self) {
+        let found = self.matches[self.match_cursor];
+        let index = found.index();
 
-        self.ui_states.result = if find_left {
-            self.dictionary.get_left(find_index).unwrap()
-        } else {
-            self.dictionary.get_right(find_index).unwrap()
+        self.ui_states.result = match found.target() {
+            MatchTarget::Left => self.dictionary.get_left(index).unwrap(),
+            MatchTarget::Right {
+                ..
+            } => self.dictionary.get_right(index).unwrap(),
         }
         .to_string();
 
         self.ui_states.evolution = format!(
             "{} = {}",
-            self.dictionary.get_left(find_index).unwrap(),
-            self.dictionary.get_all_right_to_string(find_index).unwrap()
+            self.dictionary.get_left(index).unwrap(),
+            self.dictionary.get_all_right_to_string(index).unwrap()
         );
     }
 
@@ -213,6 +213,8 @@ impl WordsTransformer {
                 self.ui_states.key.clear();
                 self.ui_states.value.clear();
 
+                // the entries are sorted again, so the found indices are outdated
+                self.no_search();
                 self.search();
             },
             Err(_) => {
@@ -248,8 +250,8 @@ impl Sandbox for WordsTransformer {
 
         WordsTransformer {
             dictionary,
-            find_index: 0,
-            find_left: false,
+            matches: Vec::new(),
+            match_cursor: 0,
             found_state: FoundState::Default,
             ui_states: UIStates::default(),
         }
@@ -267,6 +269,7 @@ impl Sandbox for WordsTransformer {
                 self.ui_states.keyword = data;
 
                 self.no_search();
+                self.suggest();
             },
             Message::ResultDataChanged(_) => {
                 // read only
@@ -344,6 +347,10 @@ impl Sandbox for WordsTransformer {
             .push(ti_keyword)
             .push(btn_search);
 
+        let t_suggestions = Text::new(self.ui_states.suggestions.join(", "))
+            .width(Length::Fill)
+            .size(SUGGESTION_FONT_SIZE);
+
         let ti_result = text_input::TextInput::new(
             &mut self.ui_states.result_state,
             "Please search a word first.",
@@ -487,6 +494,7 @@ impl Sandbox for WordsTransformer {
             .align_items(Align::Center)
             .push(btn_paste)
             .push(row_keyword)
+            .push(t_suggestions)
             .push(row_result)
             .push(ti_evolution)
             .push(btn_next)